@@ -0,0 +1,346 @@
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::tuple::Tuple;
+use crate::utils;
+
+/// An axis-aligned box that fully contains a shape, used to skip shapes a ray cannot hit.
+///
+/// A box with `min` above `max` contains nothing; that is what `empty` starts out as, so
+/// that adding points to it grows it around exactly those points.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BoundingBox {
+    pub min: Tuple,
+    pub max: Tuple,
+}
+
+impl BoundingBox {
+    pub fn new(min: Tuple, max: Tuple) -> Self {
+        BoundingBox { min, max }
+    }
+
+    pub fn empty() -> Self {
+        BoundingBox::new(
+            Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        )
+    }
+
+    /// The box around everything, for shapes such as planes that go on forever.
+    pub fn infinite() -> Self {
+        BoundingBox::new(
+            Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn is_finite(&self) -> bool {
+        [
+            self.min.x, self.min.y, self.min.z, self.max.x, self.max.y, self.max.z,
+        ]
+        .iter()
+        .all(|c| c.is_finite())
+    }
+
+    pub fn add_point(&mut self, point: &Tuple) {
+        self.min = Tuple::point(
+            self.min.x.min(point.x),
+            self.min.y.min(point.y),
+            self.min.z.min(point.z),
+        );
+        self.max = Tuple::point(
+            self.max.x.max(point.x),
+            self.max.y.max(point.y),
+            self.max.z.max(point.z),
+        );
+    }
+
+    pub fn merge(&mut self, other: &BoundingBox) {
+        if other.is_empty() {
+            return;
+        }
+        self.add_point(&other.min);
+        self.add_point(&other.max);
+    }
+
+    pub fn contains_point(&self, point: &Tuple) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+            && self.min.z <= point.z
+            && point.z <= self.max.z
+    }
+
+    pub fn contains_box(&self, other: &BoundingBox) -> bool {
+        self.contains_point(&other.min) && self.contains_point(&other.max)
+    }
+
+    /// The axis-aligned box around this box after `transform` is applied to it.
+    ///
+    /// Any infinite extent gives the infinite box, since rotating it would otherwise smear
+    /// NaNs over the corners.
+    pub fn transform(&self, transform: &Matrix) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        if !self.is_finite() {
+            return BoundingBox::infinite();
+        }
+
+        let mut transformed = BoundingBox::empty();
+        for x in [self.min.x, self.max.x] {
+            for y in [self.min.y, self.max.y] {
+                for z in [self.min.z, self.max.z] {
+                    transformed.add_point(&(transform * Tuple::point(x, y, z)));
+                }
+            }
+        }
+        transformed
+    }
+
+    pub fn centroid(&self) -> Tuple {
+        Tuple::point(
+            (self.min.x + self.max.x) / 2.0,
+            (self.min.y + self.max.y) / 2.0,
+            (self.min.z + self.max.z) / 2.0,
+        )
+    }
+
+    /// Whether the ray passes through the box at all, looking both ways along it.
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x, self.min.x, self.max.x);
+        let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y, self.min.y, self.max.y);
+        let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z, self.min.z, self.max.z);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        tmin <= tmax
+    }
+
+    /// Cuts the box in two halves across its longest axis.
+    pub fn split(&self) -> (BoundingBox, BoundingBox) {
+        let dx = self.max.x - self.min.x;
+        let dy = self.max.y - self.min.y;
+        let dz = self.max.z - self.min.z;
+        let greatest = dx.max(dy).max(dz);
+
+        let (mut x0, mut y0, mut z0) = (self.min.x, self.min.y, self.min.z);
+        let (mut x1, mut y1, mut z1) = (self.max.x, self.max.y, self.max.z);
+
+        if greatest == dx {
+            x0 += dx / 2.0;
+            x1 = x0;
+        } else if greatest == dy {
+            y0 += dy / 2.0;
+            y1 = y0;
+        } else {
+            z0 += dz / 2.0;
+            z1 = z0;
+        }
+
+        (
+            BoundingBox::new(self.min, Tuple::point(x1, y1, z1)),
+            BoundingBox::new(Tuple::point(x0, y0, z0), self.max),
+        )
+    }
+}
+
+/// Where a ray enters and leaves the slab between `min` and `max` along one axis.
+fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> (f64, f64) {
+    let tmin_numerator = min - origin;
+    let tmax_numerator = max - origin;
+
+    let (tmin, tmax) = if direction.abs() >= utils::SURFACE_EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformations::{rotation_x, rotation_y};
+    use std::f64::consts::{PI, SQRT_2};
+
+    #[test]
+    fn empty_box_contains_nothing() {
+        let b = BoundingBox::empty();
+
+        assert!(b.is_empty());
+        assert!(!b.contains_point(&Tuple::origin()));
+    }
+
+    #[test]
+    fn add_points_to_empty_box() {
+        let mut b = BoundingBox::empty();
+        b.add_point(&Tuple::point(-5.0, 2.0, 0.0));
+        b.add_point(&Tuple::point(7.0, 0.0, -3.0));
+
+        assert_eq!(b.min, Tuple::point(-5.0, 0.0, -3.0));
+        assert_eq!(b.max, Tuple::point(7.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn merge_boxes() {
+        let mut b1 = BoundingBox::new(Tuple::point(-5.0, -2.0, 0.0), Tuple::point(7.0, 4.0, 4.0));
+        let b2 = BoundingBox::new(Tuple::point(8.0, -7.0, -2.0), Tuple::point(14.0, 2.0, 8.0));
+
+        b1.merge(&b2);
+
+        assert_eq!(b1.min, Tuple::point(-5.0, -7.0, -2.0));
+        assert_eq!(b1.max, Tuple::point(14.0, 4.0, 8.0));
+    }
+
+    #[test]
+    fn box_contains_points_and_boxes() {
+        let b = BoundingBox::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+
+        assert!(b.contains_point(&Tuple::point(5.0, -2.0, 0.0)));
+        assert!(b.contains_point(&Tuple::point(8.0, 1.0, 3.0)));
+        assert!(!b.contains_point(&Tuple::point(3.0, 0.0, 3.0)));
+        assert!(!b.contains_point(&Tuple::point(8.0, 1.0, 8.0)));
+
+        let inner = BoundingBox::new(Tuple::point(6.0, -1.0, 1.0), Tuple::point(10.0, 3.0, 6.0));
+        let overlapping =
+            BoundingBox::new(Tuple::point(4.0, -3.0, -1.0), Tuple::point(10.0, 3.0, 6.0));
+        assert!(b.contains_box(&inner));
+        assert!(!b.contains_box(&overlapping));
+    }
+
+    #[test]
+    fn transform_box() {
+        let b = BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0));
+
+        let t = b.transform(&(rotation_x(PI / 4.0) * rotation_y(PI / 4.0)));
+
+        assert_eq!(
+            t.min,
+            Tuple::point(-SQRT_2, -1.7071067811865475, -1.7071067811865475)
+        );
+        assert_eq!(
+            t.max,
+            Tuple::point(SQRT_2, 1.7071067811865475, 1.7071067811865475)
+        );
+    }
+
+    #[test]
+    fn transform_infinite_box() {
+        let b = BoundingBox::new(
+            Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Tuple::point(f64::INFINITY, 0.0, f64::INFINITY),
+        );
+
+        let t = b.transform(&rotation_x(PI / 4.0));
+
+        assert!(!t.is_finite());
+        assert!(t.contains_point(&Tuple::point(0.0, 1.0E6, 0.0)));
+    }
+
+    #[test]
+    fn intersect_ray_with_box() {
+        let b = BoundingBox::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+        let cases = [
+            (
+                Tuple::point(15.0, 1.0, 2.0),
+                Tuple::vector(-1.0, 0.0, 0.0),
+                true,
+            ),
+            (
+                Tuple::point(-5.0, -1.0, 4.0),
+                Tuple::vector(1.0, 0.0, 0.0),
+                true,
+            ),
+            (
+                Tuple::point(7.0, 6.0, 5.0),
+                Tuple::vector(0.0, -1.0, 0.0),
+                true,
+            ),
+            (
+                Tuple::point(9.0, -5.0, 6.0),
+                Tuple::vector(0.0, 1.0, 0.0),
+                true,
+            ),
+            (
+                Tuple::point(8.0, 2.0, 12.0),
+                Tuple::vector(0.0, 0.0, -1.0),
+                true,
+            ),
+            (
+                Tuple::point(6.0, 0.0, -5.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                true,
+            ),
+            (
+                Tuple::point(8.0, 1.0, 3.5),
+                Tuple::vector(0.0, 0.0, 1.0),
+                true,
+            ),
+            (
+                Tuple::point(9.0, -1.0, -8.0),
+                Tuple::vector(2.0, 4.0, 6.0),
+                false,
+            ),
+            (
+                Tuple::point(8.0, 3.0, -4.0),
+                Tuple::vector(6.0, 2.0, 4.0),
+                false,
+            ),
+            (
+                Tuple::point(9.0, -1.0, -2.0),
+                Tuple::vector(4.0, 6.0, 2.0),
+                false,
+            ),
+            (
+                Tuple::point(4.0, 0.0, 9.0),
+                Tuple::vector(0.0, 0.0, -1.0),
+                false,
+            ),
+            (
+                Tuple::point(8.0, 6.0, -1.0),
+                Tuple::vector(0.0, -1.0, 0.0),
+                false,
+            ),
+            (
+                Tuple::point(12.0, 5.0, 4.0),
+                Tuple::vector(-1.0, 0.0, 0.0),
+                false,
+            ),
+        ];
+
+        for (origin, direction, hit) in cases {
+            let r = Ray::new(origin, direction.norm());
+            assert_eq!(b.intersects(&r), hit);
+        }
+    }
+
+    #[test]
+    fn split_box_along_longest_axis() {
+        let b = BoundingBox::new(Tuple::point(-1.0, -2.0, -3.0), Tuple::point(9.0, 5.5, 3.0));
+
+        let (left, right) = b.split();
+
+        assert_eq!(left.min, Tuple::point(-1.0, -2.0, -3.0));
+        assert_eq!(left.max, Tuple::point(4.0, 5.5, 3.0));
+        assert_eq!(right.min, Tuple::point(4.0, -2.0, -3.0));
+        assert_eq!(right.max, Tuple::point(9.0, 5.5, 3.0));
+    }
+}
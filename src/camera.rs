@@ -0,0 +1,271 @@
+use crate::canvas::Canvas;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::sampling::{Film, Supersampling};
+use crate::tuple::Tuple;
+use crate::world::World;
+
+/// Maps the canvas onto the scene. The canvas sits one unit in front of the camera,
+/// and `transform` describes how the world is moved relative to the camera.
+pub struct Camera {
+    pub hsize: usize,
+    pub vsize: usize,
+    pub field_of_view: f64,
+    /// Without supersampling, a single ray is cast through the center of each pixel.
+    pub supersampling: Option<Supersampling>,
+    transform: Matrix,
+    inverse_transform: Matrix,
+    half_width: f64,
+    half_height: f64,
+    pixel_size: f64,
+}
+
+impl Camera {
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
+        let half_view = (field_of_view / 2.0).tan();
+        let aspect = hsize as f64 / vsize as f64;
+
+        let (half_width, half_height) = if aspect >= 1.0 {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+
+        Self {
+            hsize,
+            vsize,
+            field_of_view,
+            supersampling: None,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            half_width,
+            half_height,
+            pixel_size: half_width * 2.0 / hsize as f64,
+        }
+    }
+
+    pub fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+
+    pub fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    pub fn pixel_size(&self) -> f64 {
+        self.pixel_size
+    }
+
+    /// Returns the ray from the camera through the center of the pixel at (x, y).
+    pub fn ray_for_pixel(&self, x: usize, y: usize) -> Ray {
+        self.ray_for_pixel_offset(x, y, 0.0, 0.0)
+    }
+
+    /// Returns the ray from the camera through the point (`dx`, `dy`) away from the center of
+    /// the pixel at (x, y), measured in pixels, with y growing downwards like the canvas.
+    pub fn ray_for_pixel_offset(&self, x: usize, y: usize, dx: f64, dy: f64) -> Ray {
+        let x_offset = (x as f64 + 0.5 + dx) * self.pixel_size;
+        let y_offset = (y as f64 + 0.5 + dy) * self.pixel_size;
+
+        // The camera looks towards -z, so +x is to the left.
+        let world_x = self.half_width - x_offset;
+        let world_y = self.half_height - y_offset;
+
+        let pixel = &self.inverse_transform * Tuple::point(world_x, world_y, -1.0);
+        let origin = &self.inverse_transform * Tuple::origin();
+        let direction = (pixel - origin).norm();
+
+        Ray::new(origin, direction)
+    }
+}
+
+pub fn render(camera: &Camera, world: &World) -> Canvas {
+    let supersampling = match &camera.supersampling {
+        Some(supersampling) => supersampling,
+        None => {
+            let mut image = Canvas::new(camera.hsize, camera.vsize);
+            for y in 0..camera.vsize {
+                for x in 0..camera.hsize {
+                    image.write_pixel(x, y, &world.color_at(&camera.ray_for_pixel(x, y)));
+                }
+            }
+            return image;
+        }
+    };
+
+    let mut film = Film::new(camera.hsize, camera.vsize, supersampling.filter);
+    for y in 0..camera.vsize {
+        for x in 0..camera.hsize {
+            for (dx, dy) in supersampling.pixel_samples(y * camera.hsize + x) {
+                let color = world.color_at(&camera.ray_for_pixel_offset(x, y, dx, dy));
+                film.add_sample(x as f64 + 0.5 + dx, y as f64 + 0.5 + dy, color);
+            }
+        }
+    }
+
+    film.develop()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::sampling::{Filter, SamplePattern};
+    use crate::transformations::{rotation_y, translation, view_transform};
+    use crate::utils;
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    #[test]
+    fn construct_camera() {
+        let c = Camera::new(160, 120, PI / 2.0);
+
+        assert_eq!(c.hsize, 160);
+        assert_eq!(c.vsize, 120);
+        assert_eq!(c.field_of_view, PI / 2.0);
+        assert_eq!(c.transform, Matrix::identity(4));
+    }
+
+    #[test]
+    fn pixel_size_for_horizontal_canvas() {
+        let c = Camera::new(200, 125, PI / 2.0);
+
+        assert!((c.pixel_size() - 0.01).abs() < utils::F64_ERROR_MARGIN);
+    }
+
+    #[test]
+    fn pixel_size_for_vertical_canvas() {
+        let c = Camera::new(125, 200, PI / 2.0);
+
+        assert!((c.pixel_size() - 0.01).abs() < utils::F64_ERROR_MARGIN);
+    }
+
+    #[test]
+    fn ray_through_center_of_canvas() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(100, 50);
+
+        assert_eq!(r.origin, Tuple::origin());
+        assert_eq!(r.direction, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn ray_through_corner_of_canvas() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(0, 0);
+
+        assert_eq!(r.origin, Tuple::origin());
+        assert_eq!(
+            r.direction,
+            Tuple::vector(0.6651864261194508, 0.3325932130597254, -0.6685123582500481)
+        );
+    }
+
+    #[test]
+    fn ray_when_camera_is_transformed() {
+        let mut c = Camera::new(201, 101, PI / 2.0);
+        c.set_transform(&(rotation_y(PI / 4.0) * translation(0.0, -2.0, 5.0)));
+        let r = c.ray_for_pixel(100, 50);
+
+        assert_eq!(r.origin, Tuple::point(0.0, 2.0, -5.0));
+        assert_eq!(
+            r.direction,
+            Tuple::vector(FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2)
+        );
+    }
+
+    #[test]
+    fn render_world_with_camera() {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        let from = Tuple::point(0.0, 0.0, -5.0);
+        let to = Tuple::origin();
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        c.set_transform(&view_transform(&from, &to, &up));
+
+        let image = render(&c, &w);
+
+        assert_eq!(
+            image.get_pixel(5, 5),
+            Color::new(0.3806609553101071, 0.47582619413763383, 0.2854957164825803)
+        );
+    }
+
+    fn default_world_camera() -> Camera {
+        let mut c = Camera::new(11, 11, PI / 6.0);
+        c.set_transform(&view_transform(
+            &Tuple::point(0.0, 0.0, -5.0),
+            &Tuple::origin(),
+            &Tuple::vector(0.0, 1.0, 0.0),
+        ));
+        c
+    }
+
+    #[test]
+    fn ray_offset_from_center_of_pixel() {
+        let c = Camera::new(201, 101, PI / 2.0);
+
+        assert_eq!(
+            c.ray_for_pixel_offset(100, 50, 0.0, 0.0).direction,
+            c.ray_for_pixel(100, 50).direction
+        );
+        assert_eq!(
+            c.ray_for_pixel_offset(0, 0, -0.5, -0.5).direction,
+            Tuple::vector(0.6662975290166695, 0.3348062210481772, -0.6662975290166696)
+        );
+    }
+
+    #[test]
+    fn supersampling_keeps_uniform_pixels_unchanged() {
+        let w = World::default();
+        let mut c = default_world_camera();
+        let single = render(&c, &w);
+        c.supersampling = Some(Supersampling::new(
+            4,
+            SamplePattern::Regular,
+            Filter::Box,
+            0,
+        ));
+
+        let image = render(&c, &w);
+
+        // Well inside the sphere and well off it, every sample sees nearly the same color.
+        assert!((image.get_pixel(5, 5).red - single.get_pixel(5, 5).red).abs() < 0.01);
+        assert_eq!(image.get_pixel(0, 0), Color::black());
+    }
+
+    #[test]
+    fn supersampling_blends_edge_pixels() {
+        let w = World::default();
+        let mut c = default_world_camera();
+        c.supersampling = Some(Supersampling::new(
+            16,
+            SamplePattern::Jittered,
+            Filter::Tent,
+            0,
+        ));
+        let single = render(&default_world_camera(), &w).get_pixel(9, 5);
+
+        let edge = render(&c, &w).get_pixel(9, 5);
+
+        // The center of the pixel just hits the sphere, but some of its samples miss.
+        assert!(edge.green > 0.0 && edge.green < single.green);
+    }
+
+    #[test]
+    fn supersampled_render_is_repeatable() {
+        let w = World::default();
+        let mut c = default_world_camera();
+        c.supersampling = Some(Supersampling::new(
+            8,
+            SamplePattern::Halton,
+            Filter::Mitchell,
+            42,
+        ));
+
+        let first = render(&c, &w);
+        let second = render(&c, &w);
+
+        assert_eq!(first.pixels, second.pixels);
+    }
+}
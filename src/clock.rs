@@ -5,6 +5,7 @@ use crate::transformations::rotation_z;
 use crate::tuple::Tuple;
 use std::f64::consts::PI;
 
+#[allow(clippy::op_ref)]
 pub fn clock() {
     let mut p = Tuple::point(30.0, 0.0, 0.0);
     let mut c = Canvas::new(100, 100);
@@ -0,0 +1,326 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::tuple::Tuple;
+use crate::utils;
+
+/// A double-napped cone around the y-axis in object space, with its apex at the origin and
+/// a radius equal to |y|. Like `Cylinder`, it may be truncated and closed with caps.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cone {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+    transform: Matrix,
+    inverse_transform: Matrix,
+    parent_inverse_transform: Matrix,
+    material: Material,
+}
+
+impl Cone {
+    pub fn new() -> Self {
+        Cone {
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            parent_inverse_transform: Matrix::identity(4),
+            material: Material::default(),
+        }
+    }
+
+    pub fn truncated(minimum: f64, maximum: f64, closed: bool) -> Self {
+        Cone {
+            minimum,
+            maximum,
+            closed,
+            ..Cone::new()
+        }
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.y.abs() <= utils::PARALLEL_EPSILON * ray.direction.abs() {
+            return;
+        }
+
+        for cap in [self.minimum, self.maximum] {
+            let time = (cap - ray.origin.y) / ray.direction.y;
+            if check_cap(ray, time, cap.abs()) {
+                xs.push(Intersection::new(time, self));
+            }
+        }
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether the ray at `time` lies within `radius` of the y-axis.
+fn check_cap(ray: &Ray, time: f64, radius: f64) -> bool {
+    let x = ray.origin.x + time * ray.direction.x;
+    let z = ray.origin.z + time * ray.direction.z;
+
+    x * x + z * z <= radius * radius
+}
+
+impl Shape for Cone {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let mut xs = vec![];
+
+        let a = ray.direction.x * ray.direction.x - ray.direction.y * ray.direction.y
+            + ray.direction.z * ray.direction.z;
+        let b = 2.0 * ray.origin.x * ray.direction.x - 2.0 * ray.origin.y * ray.direction.y
+            + 2.0 * ray.origin.z * ray.direction.z;
+        let c =
+            ray.origin.x * ray.origin.x - ray.origin.y * ray.origin.y + ray.origin.z * ray.origin.z;
+
+        let direction_length = ray.direction.abs();
+        let origin_distance = (ray.origin - Tuple::origin()).abs();
+
+        let times = if a.abs() <= utils::PARALLEL_EPSILON * direction_length * direction_length {
+            // The ray is parallel to one of the halves, so it crosses the other one exactly once.
+            if b.abs() <= utils::PARALLEL_EPSILON * direction_length * origin_distance {
+                vec![]
+            } else {
+                vec![-c / (2.0 * b)]
+            }
+        } else {
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return Intersections { objects: vec![] };
+            }
+
+            let disc_sqrt = discriminant.sqrt();
+            vec![(-b - disc_sqrt) / (2.0 * a), (-b + disc_sqrt) / (2.0 * a)]
+        };
+
+        for time in times {
+            let y = ray.origin.y + time * ray.direction.y;
+            if self.minimum < y && y < self.maximum {
+                xs.push(Intersection::new(time, self));
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        let distance = point.x * point.x + point.z * point.z;
+
+        if distance < point.y * point.y && point.y >= self.maximum - utils::SURFACE_EPSILON {
+            Tuple::vector(0.0, 1.0, 0.0)
+        } else if distance < point.y * point.y && point.y <= self.minimum + utils::SURFACE_EPSILON {
+            Tuple::vector(0.0, -1.0, 0.0)
+        } else {
+            let mut y = distance.sqrt();
+            if point.y > 0.0 {
+                y = -y;
+            }
+            Tuple::vector(point.x, y, point.z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let radius = self.minimum.abs().max(self.maximum.abs());
+        BoundingBox::new(
+            Tuple::point(-radius, self.minimum, -radius),
+            Tuple::point(radius, self.maximum, radius),
+        )
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+
+    fn get_parent_inverse_transform(&self) -> &Matrix {
+        &self.parent_inverse_transform
+    }
+
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+        self.parent_inverse_transform = parent_inverse.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: &Material) {
+        self.material = material.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformations::scaling;
+
+    #[test]
+    fn default_cone_is_infinite_and_open() {
+        let c = Cone::new();
+
+        assert_eq!(c.minimum, f64::NEG_INFINITY);
+        assert_eq!(c.maximum, f64::INFINITY);
+        assert!(!c.closed);
+        assert_eq!(c.transform, Matrix::identity(4));
+    }
+
+    #[test]
+    fn ray_intersects_cone() {
+        let c = Cone::new();
+        let cases = [
+            (
+                Tuple::point(0.0, 0.0, -5.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                5.0,
+                5.0,
+            ),
+            (
+                Tuple::point(0.0, 0.0, -5.0),
+                Tuple::vector(1.0, 1.0, 1.0),
+                5.0 * 3.0_f64.sqrt(),
+                5.0 * 3.0_f64.sqrt(),
+            ),
+            (
+                Tuple::point(1.0, 1.0, -5.0),
+                Tuple::vector(-0.5, -1.0, 1.0),
+                4.550055679356349,
+                49.449944320643645,
+            ),
+        ];
+
+        for (origin, direction, t0, t1) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction.norm()));
+
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].time - t0).abs() < 1.0E-6);
+            assert!((xs[1].time - t1).abs() < 1.0E-6);
+        }
+    }
+
+    #[test]
+    fn ray_parallel_to_one_half_intersects_once() {
+        let c = Cone::new();
+        let r = Ray::new(
+            Tuple::point(0.0, 0.0, -1.0),
+            Tuple::vector(0.0, 1.0, 1.0).norm(),
+        );
+
+        let xs = c.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert!((xs[0].time - 0.3535533905932738).abs() < utils::F64_ERROR_MARGIN);
+    }
+
+    #[test]
+    fn intersect_caps_of_closed_cone() {
+        let c = Cone::truncated(-0.5, 0.5, true);
+        let cases = [
+            (
+                Tuple::point(0.0, 0.0, -5.0),
+                Tuple::vector(0.0, 1.0, 0.0),
+                0,
+            ),
+            (
+                Tuple::point(0.0, 0.0, -0.25),
+                Tuple::vector(0.0, 1.0, 1.0),
+                2,
+            ),
+            (
+                Tuple::point(0.0, 0.0, -0.25),
+                Tuple::vector(0.0, 1.0, 0.0),
+                4,
+            ),
+        ];
+
+        for (origin, direction, count) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction.norm()));
+
+            assert_eq!(xs.len(), count);
+        }
+    }
+
+    #[test]
+    fn normal_on_cone() {
+        let c = Cone::new();
+        let cases = [
+            (Tuple::origin(), Tuple::vector(0.0, 0.0, 0.0)),
+            (
+                Tuple::point(1.0, 1.0, 1.0),
+                Tuple::vector(1.0, -(2.0_f64.sqrt()), 1.0),
+            ),
+            (Tuple::point(-1.0, -1.0, 0.0), Tuple::vector(-1.0, 1.0, 0.0)),
+        ];
+
+        for (point, normal) in cases {
+            assert_eq!(c.local_normal_at(&point), normal);
+        }
+    }
+
+    #[test]
+    fn normal_on_cone_caps() {
+        let c = Cone::truncated(-1.0, 2.0, true);
+
+        assert_eq!(
+            c.local_normal_at(&Tuple::point(0.5, 2.0, 0.0)),
+            Tuple::vector(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            c.local_normal_at(&Tuple::point(0.0, -1.0, 0.5)),
+            Tuple::vector(0.0, -1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn intersect_scaled_cone() {
+        let mut c = Cone::truncated(-1.0, 1.0, true);
+        c.set_transform(&scaling(2.0, 2.0, 2.0));
+        let r = Ray::new(Tuple::point(0.0, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+        let xs = r.intersect(&c);
+
+        // Through the top cap, the apex and the bottom cap.
+        assert_eq!(xs.len(), 4);
+        assert_eq!(xs[0].time, 3.0);
+        assert_eq!(xs[3].time, 7.0);
+    }
+
+    #[test]
+    fn intersect_hugely_scaled_cone() {
+        let mut c = Cone::truncated(-1.0, 1.0, true);
+        c.set_transform(&scaling(1.0e6, 1.0e6, 1.0e6));
+        let sides = Ray::new(
+            Tuple::point(0.0, 0.5e6, -5.0e6),
+            Tuple::vector(0.0, 0.0, 1.0),
+        );
+        let caps = Ray::new(
+            Tuple::point(0.5e6, 2.0e6, 0.0),
+            Tuple::vector(0.0, -1.0, 0.0),
+        );
+
+        let xs = sides.intersect(&c);
+
+        assert_eq!(xs.len(), 2);
+        assert!((xs[0].time - 4.5e6).abs() < 1.0e-3);
+        assert!((xs[1].time - 5.5e6).abs() < 1.0e-3);
+        // Both caps, and both halves on the way between them.
+        assert_eq!(caps.intersect(&c).len(), 4);
+    }
+}
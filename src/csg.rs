@@ -0,0 +1,367 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::tuple::Tuple;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CsgOperation {
+    /// Everything in either operand.
+    Union,
+    /// Only what is in both operands.
+    Intersection,
+    /// The left operand with the right one carved out of it.
+    Difference,
+}
+
+impl CsgOperation {
+    /// Whether a hit on one operand lies on the surface of the combined shape, given which
+    /// operand was hit and whether the ray is currently inside the left and right operands.
+    pub fn intersection_allowed(
+        &self,
+        left_hit: bool,
+        inside_left: bool,
+        inside_right: bool,
+    ) -> bool {
+        match self {
+            CsgOperation::Union => (left_hit && !inside_right) || (!left_hit && !inside_left),
+            CsgOperation::Intersection => (left_hit && inside_right) || (!left_hit && inside_left),
+            CsgOperation::Difference => (left_hit && !inside_right) || (!left_hit && inside_left),
+        }
+    }
+}
+
+/// Constructive solid geometry: two shapes combined into one by a set operation.
+///
+/// Like a group, the csg places its operands in its own space and hands the composed parent
+/// inverse down to them, so their normals come out right in world space.
+#[derive(Debug)]
+pub struct Csg {
+    pub operation: CsgOperation,
+    left: Box<dyn Shape>,
+    right: Box<dyn Shape>,
+    transform: Matrix,
+    inverse_transform: Matrix,
+    parent_inverse_transform: Matrix,
+    material: Material,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        let mut csg = Csg {
+            operation,
+            left,
+            right,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            parent_inverse_transform: Matrix::identity(4),
+            material: Material::default(),
+        };
+        csg.update_children();
+        csg
+    }
+
+    pub fn left(&self) -> &dyn Shape {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn Shape {
+        self.right.as_ref()
+    }
+
+    /// Keeps only the intersections that lie on the surface of the combined shape. The
+    /// intersections must be sorted, since we walk along the ray to track what we are inside.
+    pub fn filter_intersections<'a>(&self, xs: Intersections<'a>) -> Intersections<'a> {
+        let mut inside_left = false;
+        let mut inside_right = false;
+        let mut result = vec![];
+
+        for i in xs.objects {
+            let left_hit = self.left.includes(i.object);
+
+            if self
+                .operation
+                .intersection_allowed(left_hit, inside_left, inside_right)
+            {
+                result.push(i);
+            }
+
+            if left_hit {
+                inside_left = !inside_left;
+            } else {
+                inside_right = !inside_right;
+            }
+        }
+
+        Intersections { objects: result }
+    }
+
+    fn update_children(&mut self) {
+        let parent_inverse = &self.inverse_transform * &self.parent_inverse_transform;
+        self.left.set_parent_inverse_transform(&parent_inverse);
+        self.right.set_parent_inverse_transform(&parent_inverse);
+    }
+}
+
+impl Shape for Csg {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if !self.bounds().intersects(ray) {
+            return Intersections { objects: vec![] };
+        }
+
+        let xs: Vec<Intersection> = ray
+            .intersect(self.left.as_ref())
+            .objects
+            .into_iter()
+            .chain(ray.intersect(self.right.as_ref()).objects)
+            .collect();
+
+        self.filter_intersections(Intersections::new(xs))
+    }
+
+    /// Rays only ever hit the operands, so a csg never has to report a normal of its own.
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        panic!("a csg has no surface of its own; normals are computed on its operands")
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = self.left.parent_space_bounds();
+        bounds.merge(&self.right.parent_space_bounds());
+        bounds
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        self.left.divide(threshold);
+        self.right.divide(threshold);
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.left.includes(other) || self.right.includes(other)
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+        self.update_children();
+    }
+
+    fn get_parent_inverse_transform(&self) -> &Matrix {
+        &self.parent_inverse_transform
+    }
+
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+        self.parent_inverse_transform = parent_inverse.clone();
+        self.update_children();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    /// Sets the material of the csg and of both operands.
+    fn set_material(&mut self, material: &Material) {
+        self.material = material.clone();
+        self.left.set_material(material);
+        self.right.set_material(material);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::Cube;
+    use crate::cylinder::Cylinder;
+    use crate::shape::same_shape;
+    use crate::sphere::Sphere;
+    use crate::transformations::{scaling, translation};
+
+    #[test]
+    fn create_csg() {
+        let c = Csg::new(
+            CsgOperation::Union,
+            Box::new(Sphere::unit()),
+            Box::new(Cube::new()),
+        );
+
+        assert_eq!(c.operation, CsgOperation::Union);
+        assert_eq!(
+            c.left().get_parent_inverse_transform(),
+            &Matrix::identity(4)
+        );
+    }
+
+    #[test]
+    fn evaluate_rule_for_csg_operation() {
+        use CsgOperation::*;
+        let cases = [
+            (Union, true, true, true, false),
+            (Union, true, true, false, true),
+            (Union, true, false, true, false),
+            (Union, true, false, false, true),
+            (Union, false, true, true, false),
+            (Union, false, true, false, false),
+            (Union, false, false, true, true),
+            (Union, false, false, false, true),
+            (Intersection, true, true, true, true),
+            (Intersection, true, true, false, false),
+            (Intersection, true, false, true, true),
+            (Intersection, true, false, false, false),
+            (Intersection, false, true, true, true),
+            (Intersection, false, true, false, true),
+            (Intersection, false, false, true, false),
+            (Intersection, false, false, false, false),
+            (Difference, true, true, true, false),
+            (Difference, true, true, false, true),
+            (Difference, true, false, true, false),
+            (Difference, true, false, false, true),
+            (Difference, false, true, true, true),
+            (Difference, false, true, false, true),
+            (Difference, false, false, true, false),
+            (Difference, false, false, false, false),
+        ];
+
+        for (operation, left_hit, inside_left, inside_right, allowed) in cases {
+            assert_eq!(
+                operation.intersection_allowed(left_hit, inside_left, inside_right),
+                allowed
+            );
+        }
+    }
+
+    #[test]
+    fn filter_list_of_intersections() {
+        let cases = [
+            (CsgOperation::Union, 0, 3),
+            (CsgOperation::Intersection, 1, 2),
+            (CsgOperation::Difference, 0, 1),
+        ];
+
+        for (operation, x0, x1) in cases {
+            let c = Csg::new(operation, Box::new(Sphere::unit()), Box::new(Cube::new()));
+            let xs = Intersections::new(vec![
+                Intersection::new(1.0, c.left()),
+                Intersection::new(2.0, c.right()),
+                Intersection::new(3.0, c.left()),
+                Intersection::new(4.0, c.right()),
+            ]);
+
+            let result = c.filter_intersections(xs.clone());
+
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0], xs[x0]);
+            assert_eq!(result[1], xs[x1]);
+        }
+    }
+
+    #[test]
+    fn ray_misses_csg() {
+        let c = Csg::new(
+            CsgOperation::Union,
+            Box::new(Sphere::unit()),
+            Box::new(Cube::new()),
+        );
+        let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        assert!(c.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_hits_csg() {
+        let mut s2 = Sphere::unit();
+        s2.set_transform(&translation(0.0, 0.0, 0.5));
+        let c = Csg::new(CsgOperation::Union, Box::new(Sphere::unit()), Box::new(s2));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        let xs = c.local_intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].time, 4.0);
+        assert!(same_shape(xs[0].object, c.left()));
+        assert_eq!(xs[1].time, 6.5);
+        assert!(same_shape(xs[1].object, c.right()));
+    }
+
+    #[test]
+    fn lens_from_intersection_of_two_spheres() {
+        let mut s1 = Sphere::unit();
+        s1.set_transform(&translation(0.0, 0.0, -0.5));
+        let mut s2 = Sphere::unit();
+        s2.set_transform(&translation(0.0, 0.0, 0.5));
+        let c = Csg::new(CsgOperation::Intersection, Box::new(s1), Box::new(s2));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        let xs = c.local_intersect(&r);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].time, 4.5);
+        assert!(same_shape(xs[0].object, c.right()));
+        assert_eq!(xs[1].time, 5.5);
+        assert!(same_shape(xs[1].object, c.left()));
+    }
+
+    #[test]
+    fn hole_carved_through_cube() {
+        let mut drill = Cylinder::truncated(-2.0, 2.0, true);
+        drill.set_transform(&scaling(0.5, 1.0, 0.5));
+        let mut c = Csg::new(
+            CsgOperation::Difference,
+            Box::new(Cube::new()),
+            Box::new(drill),
+        );
+        c.set_transform(&translation(0.0, 0.0, 3.0));
+
+        let through_hole = Ray::new(Tuple::point(0.0, 5.0, 3.0), Tuple::vector(0.0, -1.0, 0.0));
+        let beside_hole = Ray::new(Tuple::point(0.75, 5.0, 3.0), Tuple::vector(0.0, -1.0, 0.0));
+
+        assert!(through_hole.intersect(&c).is_empty());
+        let xs = beside_hole.intersect(&c);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].time, 4.0);
+        assert_eq!(xs[1].time, 6.0);
+    }
+
+    #[test]
+    fn normal_on_carved_wall_points_into_hole() {
+        let mut drill = Cylinder::truncated(-2.0, 2.0, true);
+        drill.set_transform(&scaling(0.5, 1.0, 0.5));
+        let mut c = Csg::new(
+            CsgOperation::Difference,
+            Box::new(Cube::new()),
+            Box::new(drill),
+        );
+        c.set_transform(&translation(0.0, 0.0, 3.0));
+        let r = Ray::new(Tuple::point(-5.0, 0.0, 3.0), Tuple::vector(1.0, 0.0, 0.0));
+
+        let xs = r.intersect(&c);
+        let comps = xs[1].prepare_computations(&r);
+
+        // Entering the cube at x = -1, then leaving the material into the hole at x = -0.5.
+        assert_eq!(xs.len(), 4);
+        assert_eq!(comps.point, Tuple::point(-0.5, 0.0, 3.0));
+        assert_eq!(comps.normal_vector, Tuple::vector(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn csg_includes_its_operands() {
+        let c = Csg::new(
+            CsgOperation::Union,
+            Box::new(Sphere::unit()),
+            Box::new(Cube::new()),
+        );
+        let outsider = Sphere::unit();
+
+        assert!(c.includes(c.left()));
+        assert!(c.includes(c.right()));
+        assert!(!c.includes(&outsider));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::tuple::Tuple;
+use crate::utils;
+
+/// An axis-aligned cube spanning -1 to 1 along every axis in object space.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cube {
+    transform: Matrix,
+    inverse_transform: Matrix,
+    parent_inverse_transform: Matrix,
+    material: Material,
+}
+
+impl Cube {
+    pub fn new() -> Self {
+        Cube {
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            parent_inverse_transform: Matrix::identity(4),
+            material: Material::default(),
+        }
+    }
+}
+
+impl Default for Cube {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where a ray enters and leaves the slab between the planes at -1 and 1 along one axis.
+fn check_axis(origin: f64, direction: f64) -> (f64, f64) {
+    let tmin_numerator = -1.0 - origin;
+    let tmax_numerator = 1.0 - origin;
+
+    let (tmin, tmax) = if direction.abs() >= utils::SURFACE_EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+impl Shape for Cube {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x);
+        let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y);
+        let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z);
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        if tmin > tmax {
+            return Intersections { objects: vec![] };
+        }
+
+        Intersections {
+            objects: vec![Intersection::new(tmin, self), Intersection::new(tmax, self)],
+        }
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        let max_component = point.x.abs().max(point.y.abs()).max(point.z.abs());
+
+        if max_component == point.x.abs() {
+            Tuple::vector(point.x, 0.0, 0.0)
+        } else if max_component == point.y.abs() {
+            Tuple::vector(0.0, point.y, 0.0)
+        } else {
+            Tuple::vector(0.0, 0.0, point.z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+
+    fn get_parent_inverse_transform(&self) -> &Matrix {
+        &self.parent_inverse_transform
+    }
+
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+        self.parent_inverse_transform = parent_inverse.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: &Material) {
+        self.material = material.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::transformations::{scaling, translation};
+
+    #[test]
+    fn test_cube_default_transform() {
+        let c = Cube::new();
+        assert_eq!(c.transform, Matrix::identity(4));
+    }
+
+    #[test]
+    fn cube_can_be_assigned_material() {
+        let mut c = Cube::new();
+        let m = Material::new(Color::new(1.0, 0.0, 1.0), 1.0, 2.0, 3.0, 4.0);
+        c.set_material(&m);
+        assert_eq!(c.material, m);
+    }
+
+    #[test]
+    fn ray_intersects_cube() {
+        let c = Cube::new();
+        let cases = [
+            (
+                Tuple::point(5.0, 0.5, 0.0),
+                Tuple::vector(-1.0, 0.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Tuple::point(-5.0, 0.5, 0.0),
+                Tuple::vector(1.0, 0.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Tuple::point(0.5, 5.0, 0.0),
+                Tuple::vector(0.0, -1.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Tuple::point(0.5, -5.0, 0.0),
+                Tuple::vector(0.0, 1.0, 0.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Tuple::point(0.5, 0.0, 5.0),
+                Tuple::vector(0.0, 0.0, -1.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Tuple::point(0.5, 0.0, -5.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Tuple::point(0.0, 0.5, 0.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                -1.0,
+                1.0,
+            ),
+        ];
+
+        for (origin, direction, t1, t2) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction));
+
+            assert_eq!(xs.len(), 2);
+            assert_eq!(xs[0].time, t1);
+            assert_eq!(xs[1].time, t2);
+        }
+    }
+
+    #[test]
+    fn ray_misses_cube() {
+        let c = Cube::new();
+        let cases = [
+            (
+                Tuple::point(-2.0, 0.0, 0.0),
+                Tuple::vector(0.2673, 0.5345, 0.8018),
+            ),
+            (
+                Tuple::point(0.0, -2.0, 0.0),
+                Tuple::vector(0.8018, 0.2673, 0.5345),
+            ),
+            (
+                Tuple::point(0.0, 0.0, -2.0),
+                Tuple::vector(0.5345, 0.8018, 0.2673),
+            ),
+            (Tuple::point(2.0, 0.0, 2.0), Tuple::vector(0.0, 0.0, -1.0)),
+            (Tuple::point(0.0, 2.0, 2.0), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(2.0, 2.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0)),
+        ];
+
+        for (origin, direction) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction));
+
+            assert_eq!(xs.len(), 0);
+        }
+    }
+
+    #[test]
+    fn normal_on_surface_of_cube() {
+        let c = Cube::new();
+        let cases = [
+            (Tuple::point(1.0, 0.5, -0.8), Tuple::vector(1.0, 0.0, 0.0)),
+            (Tuple::point(-1.0, -0.2, 0.9), Tuple::vector(-1.0, 0.0, 0.0)),
+            (Tuple::point(-0.4, 1.0, -0.1), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::point(0.3, -1.0, -0.7), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(-0.6, 0.3, 1.0), Tuple::vector(0.0, 0.0, 1.0)),
+            (Tuple::point(0.4, 0.4, -1.0), Tuple::vector(0.0, 0.0, -1.0)),
+            (Tuple::point(1.0, 1.0, 1.0), Tuple::vector(1.0, 0.0, 0.0)),
+            (
+                Tuple::point(-1.0, -1.0, -1.0),
+                Tuple::vector(-1.0, 0.0, 0.0),
+            ),
+        ];
+
+        for (point, normal) in cases {
+            assert_eq!(c.local_normal_at(&point), normal);
+        }
+    }
+
+    #[test]
+    fn intersect_transformed_cube() {
+        let mut c = Cube::new();
+        c.set_transform(&(translation(0.0, 0.0, 10.0) * scaling(2.0, 1.0, 1.0)));
+        let r = Ray::new(Tuple::point(0.0, 0.5, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        let xs = r.intersect(&c);
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].time, 9.0);
+        assert_eq!(xs[1].time, 11.0);
+    }
+}
@@ -0,0 +1,364 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::tuple::Tuple;
+use crate::utils;
+
+/// A cylinder of radius 1 around the y-axis in object space, optionally truncated
+/// at `minimum` and `maximum` (exclusive) and capped at both ends when `closed`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cylinder {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+    transform: Matrix,
+    inverse_transform: Matrix,
+    parent_inverse_transform: Matrix,
+    material: Material,
+}
+
+impl Cylinder {
+    pub fn new() -> Self {
+        Cylinder {
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            parent_inverse_transform: Matrix::identity(4),
+            material: Material::default(),
+        }
+    }
+
+    pub fn truncated(minimum: f64, maximum: f64, closed: bool) -> Self {
+        Cylinder {
+            minimum,
+            maximum,
+            closed,
+            ..Cylinder::new()
+        }
+    }
+
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.y.abs() <= utils::PARALLEL_EPSILON * ray.direction.abs() {
+            return;
+        }
+
+        for cap in [self.minimum, self.maximum] {
+            let time = (cap - ray.origin.y) / ray.direction.y;
+            if check_cap(ray, time) {
+                xs.push(Intersection::new(time, self));
+            }
+        }
+    }
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether the ray at `time` lies within the unit radius of the y-axis.
+fn check_cap(ray: &Ray, time: f64) -> bool {
+    let x = ray.origin.x + time * ray.direction.x;
+    let z = ray.origin.z + time * ray.direction.z;
+
+    x * x + z * z <= 1.0
+}
+
+impl Shape for Cylinder {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let mut xs = vec![];
+
+        let a = ray.direction.x * ray.direction.x + ray.direction.z * ray.direction.z;
+
+        // A ray parallel to the y-axis can only hit the caps.
+        if a > utils::PARALLEL_EPSILON * ray.direction.dot(&ray.direction) {
+            let b = 2.0 * ray.origin.x * ray.direction.x + 2.0 * ray.origin.z * ray.direction.z;
+            let c = ray.origin.x * ray.origin.x + ray.origin.z * ray.origin.z - 1.0;
+
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return Intersections { objects: vec![] };
+            }
+
+            let disc_sqrt = discriminant.sqrt();
+            let t0 = (-b - disc_sqrt) / (2.0 * a);
+            let t1 = (-b + disc_sqrt) / (2.0 * a);
+
+            for time in [t0, t1] {
+                let y = ray.origin.y + time * ray.direction.y;
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(time, self));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        let distance = point.x * point.x + point.z * point.z;
+
+        if distance < 1.0 && point.y >= self.maximum - utils::SURFACE_EPSILON {
+            Tuple::vector(0.0, 1.0, 0.0)
+        } else if distance < 1.0 && point.y <= self.minimum + utils::SURFACE_EPSILON {
+            Tuple::vector(0.0, -1.0, 0.0)
+        } else {
+            Tuple::vector(point.x, 0.0, point.z)
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Tuple::point(-1.0, self.minimum, -1.0),
+            Tuple::point(1.0, self.maximum, 1.0),
+        )
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+
+    fn get_parent_inverse_transform(&self) -> &Matrix {
+        &self.parent_inverse_transform
+    }
+
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+        self.parent_inverse_transform = parent_inverse.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: &Material) {
+        self.material = material.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformations::scaling;
+
+    #[test]
+    fn default_cylinder_is_infinite_and_open() {
+        let c = Cylinder::new();
+
+        assert_eq!(c.minimum, f64::NEG_INFINITY);
+        assert_eq!(c.maximum, f64::INFINITY);
+        assert!(!c.closed);
+        assert_eq!(c.transform, Matrix::identity(4));
+    }
+
+    #[test]
+    fn ray_misses_cylinder() {
+        let c = Cylinder::new();
+        let cases = [
+            (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::origin(), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(1.0, 1.0, 1.0)),
+        ];
+
+        for (origin, direction) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction.norm()));
+
+            assert_eq!(xs.len(), 0);
+        }
+    }
+
+    #[test]
+    fn ray_strikes_cylinder() {
+        let c = Cylinder::new();
+        let cases = [
+            (
+                Tuple::point(1.0, 0.0, -5.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                5.0,
+                5.0,
+            ),
+            (
+                Tuple::point(0.0, 0.0, -5.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Tuple::point(0.5, 0.0, -5.0),
+                Tuple::vector(0.1, 1.0, 1.0),
+                6.80798191702732,
+                7.088723439378861,
+            ),
+        ];
+
+        for (origin, direction, t0, t1) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction.norm()));
+
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].time - t0).abs() < utils::F64_ERROR_MARGIN);
+            assert!((xs[1].time - t1).abs() < utils::F64_ERROR_MARGIN);
+        }
+    }
+
+    #[test]
+    fn normal_on_cylinder() {
+        let c = Cylinder::new();
+        let cases = [
+            (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0)),
+            (Tuple::point(0.0, 5.0, -1.0), Tuple::vector(0.0, 0.0, -1.0)),
+            (Tuple::point(0.0, -2.0, 1.0), Tuple::vector(0.0, 0.0, 1.0)),
+            (Tuple::point(-1.0, 1.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0)),
+        ];
+
+        for (point, normal) in cases {
+            assert_eq!(c.local_normal_at(&point), normal);
+        }
+    }
+
+    #[test]
+    fn normal_on_non_uniformly_scaled_cylinder() {
+        let mut c = Cylinder::new();
+        c.set_transform(&scaling(2.0, 1.0, 0.5));
+
+        let n = c.normal_at(&Tuple::point(2.0_f64.sqrt(), 3.0, 0.5_f64.sqrt() / 2.0));
+
+        assert_eq!(
+            n,
+            Tuple::vector(0.24253562503633297, 0.0, 0.9701425001453319)
+        );
+    }
+
+    #[test]
+    fn intersect_truncated_cylinder() {
+        let c = Cylinder::truncated(1.0, 2.0, false);
+        let cases = [
+            (Tuple::point(0.0, 1.5, 0.0), Tuple::vector(0.1, 1.0, 0.0), 0),
+            (
+                Tuple::point(0.0, 3.0, -5.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                0,
+            ),
+            (
+                Tuple::point(0.0, 0.0, -5.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                0,
+            ),
+            (
+                Tuple::point(0.0, 2.0, -5.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                0,
+            ),
+            (
+                Tuple::point(0.0, 1.0, -5.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                0,
+            ),
+            (
+                Tuple::point(0.0, 1.5, -2.0),
+                Tuple::vector(0.0, 0.0, 1.0),
+                2,
+            ),
+        ];
+
+        for (origin, direction, count) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction.norm()));
+
+            assert_eq!(xs.len(), count);
+        }
+    }
+
+    #[test]
+    fn intersect_caps_of_closed_cylinder() {
+        let c = Cylinder::truncated(1.0, 2.0, true);
+        let cases = [
+            (
+                Tuple::point(0.0, 3.0, 0.0),
+                Tuple::vector(0.0, -1.0, 0.0),
+                2,
+            ),
+            (
+                Tuple::point(0.0, 3.0, -2.0),
+                Tuple::vector(0.0, -1.0, 2.0),
+                2,
+            ),
+            (
+                Tuple::point(0.0, 4.0, -2.0),
+                Tuple::vector(0.0, -1.0, 1.0),
+                2,
+            ),
+            (
+                Tuple::point(0.0, 0.0, -2.0),
+                Tuple::vector(0.0, 1.0, 2.0),
+                2,
+            ),
+            (
+                Tuple::point(0.0, -1.0, -2.0),
+                Tuple::vector(0.0, 1.0, 1.0),
+                2,
+            ),
+        ];
+
+        for (origin, direction, count) in cases {
+            let xs = c.local_intersect(&Ray::new(origin, direction.norm()));
+
+            assert_eq!(xs.len(), count);
+        }
+    }
+
+    #[test]
+    fn normal_on_cylinder_caps() {
+        let c = Cylinder::truncated(1.0, 2.0, true);
+        let cases = [
+            (Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(0.5, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(0.0, 1.0, 0.5), Tuple::vector(0.0, -1.0, 0.0)),
+            (Tuple::point(0.0, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::point(0.5, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+            (Tuple::point(0.0, 2.0, 0.5), Tuple::vector(0.0, 1.0, 0.0)),
+        ];
+
+        for (point, normal) in cases {
+            assert_eq!(c.local_normal_at(&point), normal);
+        }
+    }
+
+    #[test]
+    fn intersections_are_sorted_including_caps() {
+        let c = Cylinder::truncated(1.0, 2.0, true);
+        let r = Ray::new(
+            Tuple::point(0.0, 3.0, -2.0),
+            Tuple::vector(0.0, -1.0, 2.0).norm(),
+        );
+
+        let xs = c.local_intersect(&r);
+
+        assert!(xs[0].time <= xs[1].time);
+    }
+
+    #[test]
+    fn intersect_hugely_scaled_cylinder() {
+        let mut c = Cylinder::truncated(-1.0, 1.0, true);
+        c.set_transform(&scaling(1.0e6, 1.0e6, 1.0e6));
+        let sides = Ray::new(Tuple::point(0.0, 0.0, -2.0e6), Tuple::vector(0.0, 0.0, 1.0));
+        let caps = Ray::new(Tuple::point(0.0, 2.0e6, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+        assert_eq!(sides.intersect(&c).len(), 2);
+        assert_eq!(caps.intersect(&c).len(), 2);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::color::Color;
+use crate::matrix::Matrix;
+use crate::texture::UvTexture;
+use crate::tuple::Tuple;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+/// What a ray sees when it leaves the scene without hitting anything: a panorama around the
+/// whole world, looked up by the direction of the ray alone.
+///
+/// The texture is read as an equirectangular map, the usual layout of HDR environment maps:
+/// u runs once around the horizon and v from straight down to straight up. The middle of the
+/// map lies along the positive z-axis, in front of a camera looking down it.
+#[derive(Debug, Clone)]
+pub struct Environment {
+    texture: Rc<dyn UvTexture>,
+    /// Scales the brightness of the map, to balance it against the lights in the scene.
+    pub intensity: f64,
+    transform: Matrix,
+    inverse_transform: Matrix,
+}
+
+impl Environment {
+    pub fn new(texture: Rc<dyn UvTexture>) -> Self {
+        Environment {
+            texture,
+            intensity: 1.0,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+        }
+    }
+
+    pub fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    /// Orients the panorama around the scene. Only rotations make sense here, since the
+    /// environment is infinitely far away.
+    pub fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+
+    /// The color of the environment in `direction`.
+    pub fn color_at(&self, direction: &Tuple) -> Color {
+        let mut direction = &self.inverse_transform * *direction;
+        direction.w = 0;
+        let (u, v) = equirectangular_map(&direction.norm());
+
+        self.texture.uv_pattern_at(u, v) * self.intensity
+    }
+}
+
+/// The texture coordinates of the unit vector `direction`. Seen from inside the sphere,
+/// u grows to the right, so the map is not mirrored.
+fn equirectangular_map(direction: &Tuple) -> (f64, f64) {
+    let theta = direction.x.atan2(direction.z);
+    let phi = direction.y.clamp(-1.0, 1.0).acos();
+
+    (0.5 + theta / (2.0 * PI), 1.0 - phi / PI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformations::rotation_y;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    /// Shows the texture coordinates it is looked up with as the red and green channels.
+    #[derive(Debug)]
+    struct UvCoordinates;
+
+    impl UvTexture for UvCoordinates {
+        fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+            Color::new(u, v, 0.0)
+        }
+    }
+
+    #[test]
+    fn environment_maps_directions_to_equirectangular_coordinates() {
+        let env = Environment::new(Rc::new(UvCoordinates));
+        let cases = [
+            (Tuple::vector(0.0, 0.0, 1.0), 0.5, 0.5),
+            (Tuple::vector(1.0, 0.0, 0.0), 0.75, 0.5),
+            (Tuple::vector(-1.0, 0.0, 0.0), 0.25, 0.5),
+            (Tuple::vector(0.0, 1.0, 0.0), 0.5, 1.0),
+            (Tuple::vector(0.0, -1.0, 0.0), 0.5, 0.0),
+            (Tuple::vector(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2), 0.5, 0.75),
+        ];
+
+        for (direction, u, v) in cases {
+            assert_eq!(env.color_at(&direction), Color::new(u, v, 0.0));
+        }
+    }
+
+    #[test]
+    fn environment_lookup_ignores_length_of_direction() {
+        let env = Environment::new(Rc::new(UvCoordinates));
+
+        assert_eq!(
+            env.color_at(&Tuple::vector(0.0, 3.0, 3.0)),
+            env.color_at(&Tuple::vector(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2))
+        );
+    }
+
+    #[test]
+    fn rotated_environment() {
+        let mut env = Environment::new(Rc::new(UvCoordinates));
+        env.set_transform(&rotation_y(PI / 2.0));
+
+        // The middle of the map now lies along the positive x-axis.
+        assert_eq!(
+            env.color_at(&Tuple::vector(1.0, 0.0, 0.0)),
+            Color::new(0.5, 0.5, 0.0)
+        );
+    }
+
+    #[test]
+    fn environment_intensity_scales_map() {
+        let mut env = Environment::new(Rc::new(UvCoordinates));
+        env.intensity = 2.0;
+
+        assert_eq!(
+            env.color_at(&Tuple::vector(0.0, 0.0, 1.0)),
+            Color::new(1.0, 1.0, 0.0)
+        );
+    }
+}
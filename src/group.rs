@@ -0,0 +1,470 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::tuple::Tuple;
+
+/// A collection of shapes that are transformed together as one.
+///
+/// Every child keeps the composed inverse of all groups above it, so that it can find its way
+/// back to world space on its own. Transforms should therefore be set before adding a group to
+/// its parent, i.e. assemblies are built bottom-up; changing them afterwards is propagated too,
+/// but costs a walk over the whole subtree.
+#[derive(Debug)]
+pub struct Group {
+    children: Vec<Box<dyn Shape>>,
+    /// The box around all children, kept up to date as they are added.
+    bounds: BoundingBox,
+    transform: Matrix,
+    inverse_transform: Matrix,
+    parent_inverse_transform: Matrix,
+    material: Material,
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Group {
+            children: vec![],
+            bounds: BoundingBox::empty(),
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            parent_inverse_transform: Matrix::identity(4),
+            material: Material::default(),
+        }
+    }
+
+    pub fn add_child(&mut self, mut child: Box<dyn Shape>) {
+        child.set_parent_inverse_transform(&self.children_parent_inverse());
+        self.bounds.merge(&child.parent_space_bounds());
+        self.children.push(child);
+    }
+
+    pub fn children(&self) -> &[Box<dyn Shape>] {
+        &self.children
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// The inverse that takes a world space point into the space the children are placed in.
+    fn children_parent_inverse(&self) -> Matrix {
+        &self.inverse_transform * &self.parent_inverse_transform
+    }
+
+    /// Moves the children into two new subgroups, by which side of the middle of their
+    /// centroids they fall along the axis where those centroids are spread the most.
+    /// Unbounded children such as planes cannot be placed on either side and stay here.
+    fn split_children(&mut self) {
+        let mut centroids = BoundingBox::empty();
+        for child in &self.children {
+            let bounds = child.parent_space_bounds();
+            if bounds.is_finite() {
+                centroids.add_point(&bounds.centroid());
+            }
+        }
+        if centroids.is_empty() {
+            return;
+        }
+        let (left_box, _) = centroids.split();
+
+        let mut left = vec![];
+        let mut right = vec![];
+        let mut remaining = vec![];
+        for child in self.children.drain(..) {
+            let bounds = child.parent_space_bounds();
+            if !bounds.is_finite() {
+                remaining.push(child);
+            } else if left_box.contains_point(&bounds.centroid()) {
+                left.push(child);
+            } else {
+                right.push(child);
+            }
+        }
+
+        // All centroids coincide, so there is no way to tell the children apart.
+        if left.is_empty() || right.is_empty() {
+            self.children = remaining.into_iter().chain(left).chain(right).collect();
+            return;
+        }
+
+        self.children = remaining;
+        for part in [left, right] {
+            let mut subgroup = Group::new();
+            for child in part {
+                subgroup.add_child(child);
+            }
+            self.add_child(Box::new(subgroup));
+        }
+    }
+
+    fn update_children(&mut self) {
+        let parent_inverse = self.children_parent_inverse();
+        for child in self.children.iter_mut() {
+            child.set_parent_inverse_transform(&parent_inverse);
+        }
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shape for Group {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if !self.bounds.intersects(ray) {
+            return Intersections { objects: vec![] };
+        }
+
+        let xs: Vec<Intersection> = self
+            .children
+            .iter()
+            .flat_map(|child| ray.intersect(child.as_ref()).objects)
+            .collect();
+
+        Intersections::new(xs)
+    }
+
+    /// Rays only ever hit the children, so a group never has to report a normal of its own.
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        panic!("a group has no surface; normals are computed on its children")
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        if self.children.len() > threshold {
+            self.split_children();
+        }
+        for child in self.children.iter_mut() {
+            child.divide(threshold);
+        }
+    }
+
+    fn includes(&self, other: &dyn Shape) -> bool {
+        self.children.iter().any(|child| child.includes(other))
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+        self.update_children();
+    }
+
+    fn get_parent_inverse_transform(&self) -> &Matrix {
+        &self.parent_inverse_transform
+    }
+
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+        self.parent_inverse_transform = parent_inverse.clone();
+        self.update_children();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    /// Sets the material of the group and of everything in it.
+    fn set_material(&mut self, material: &Material) {
+        self.material = material.clone();
+        for child in self.children.iter_mut() {
+            child.set_material(material);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::plane::Plane;
+    use crate::shape::same_shape;
+    use crate::sphere::Sphere;
+    use crate::transformations::{rotation_y, scaling, translation};
+    use crate::triangle::Triangle;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn create_group() {
+        let g = Group::new();
+
+        assert_eq!(g.transform, Matrix::identity(4));
+        assert!(g.is_empty());
+    }
+
+    #[test]
+    fn add_child_to_group() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::unit()));
+
+        assert_eq!(g.len(), 1);
+        assert_eq!(
+            g.children()[0].get_parent_inverse_transform(),
+            &Matrix::identity(4)
+        );
+    }
+
+    #[test]
+    fn intersect_ray_with_empty_group() {
+        let g = Group::new();
+        let r = Ray::new(Tuple::origin(), Tuple::vector(0.0, 0.0, 1.0));
+
+        assert!(g.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn intersect_ray_with_nonempty_group() {
+        let mut g = Group::new();
+        let s1 = Sphere::unit();
+        let mut s2 = Sphere::unit();
+        s2.set_transform(&translation(0.0, 0.0, -3.0));
+        let mut s3 = Sphere::unit();
+        s3.set_transform(&translation(5.0, 0.0, 0.0));
+        g.add_child(Box::new(s1));
+        g.add_child(Box::new(s2));
+        g.add_child(Box::new(s3));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        let xs = g.local_intersect(&r);
+
+        let s1 = g.children()[0].as_ref();
+        let s2 = g.children()[1].as_ref();
+        assert_eq!(xs.len(), 4);
+        assert!(same_shape(xs[0].object, s2));
+        assert!(same_shape(xs[1].object, s2));
+        assert!(same_shape(xs[2].object, s1));
+        assert!(same_shape(xs[3].object, s1));
+    }
+
+    #[test]
+    fn intersect_transformed_group() {
+        let mut g = Group::new();
+        g.set_transform(&scaling(2.0, 2.0, 2.0));
+        let mut s = Sphere::unit();
+        s.set_transform(&translation(5.0, 0.0, 0.0));
+        g.add_child(Box::new(s));
+        let r = Ray::new(Tuple::point(10.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        assert_eq!(r.intersect(&g).len(), 2);
+    }
+
+    /// A sphere translated within a scaled group, within a rotated group.
+    fn nested_sphere(g2_scaling: Matrix) -> Group {
+        let mut g1 = Group::new();
+        g1.set_transform(&rotation_y(PI / 2.0));
+        let mut g2 = Group::new();
+        g2.set_transform(&g2_scaling);
+        let mut s = Sphere::unit();
+        s.set_transform(&translation(5.0, 0.0, 0.0));
+        g2.add_child(Box::new(s));
+        g1.add_child(Box::new(g2));
+        g1
+    }
+
+    /// Digs the sphere out of the assembly by shooting a ray at it, which sits on the negative z-axis.
+    fn innermost(g1: &Group) -> &dyn Shape {
+        let r = Ray::new(Tuple::origin(), Tuple::vector(0.0, 0.0, -1.0));
+        r.intersect(g1)[0].object
+    }
+
+    #[test]
+    fn convert_point_from_world_to_object_space() {
+        let g1 = nested_sphere(scaling(2.0, 2.0, 2.0));
+        let s = innermost(&g1);
+
+        let p = s.world_to_object(&Tuple::point(-2.0, 0.0, -10.0));
+
+        assert_eq!(p, Tuple::point(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn convert_normal_from_object_to_world_space() {
+        let g1 = nested_sphere(scaling(1.0, 2.0, 3.0));
+        let s = innermost(&g1);
+        let third = 3.0_f64.sqrt() / 3.0;
+
+        let n = s.normal_to_world(&Tuple::vector(third, third, third));
+
+        assert_eq!(
+            n,
+            Tuple::vector(0.2857142857142857, 0.42857142857142855, -0.8571428571428571)
+        );
+    }
+
+    #[test]
+    fn find_normal_on_child_object() {
+        let g1 = nested_sphere(scaling(1.0, 2.0, 3.0));
+        let s = innermost(&g1);
+
+        let n = s.normal_at(&Tuple::point(1.7321, 1.1547, -5.5774));
+
+        assert_eq!(
+            n,
+            Tuple::vector(
+                0.28570368184140726,
+                0.42854315178114105,
+                -0.8571605294481017
+            )
+        );
+    }
+
+    #[test]
+    fn transforming_group_after_adding_children_propagates() {
+        let mut g1 = Group::new();
+        let mut g2 = Group::new();
+        g2.set_transform(&scaling(2.0, 2.0, 2.0));
+        let mut s = Sphere::unit();
+        s.set_transform(&translation(5.0, 0.0, 0.0));
+        g2.add_child(Box::new(s));
+        g1.add_child(Box::new(g2));
+
+        g1.set_transform(&rotation_y(PI / 2.0));
+
+        let p = innermost(&g1).world_to_object(&Tuple::point(-2.0, 0.0, -10.0));
+        assert_eq!(p, Tuple::point(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn group_material_is_applied_to_children() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::unit()));
+        let m = Material::new(Color::new(1.0, 0.0, 1.0), 1.0, 2.0, 3.0, 4.0);
+
+        g.set_material(&m);
+
+        assert_eq!(g.children()[0].get_material(), &m);
+    }
+
+    #[test]
+    fn group_bounds_contain_all_children() {
+        let mut s = Sphere::unit();
+        s.set_transform(&(translation(2.0, 5.0, -3.0) * scaling(2.0, 2.0, 2.0)));
+        let mut c = crate::cylinder::Cylinder::truncated(-2.0, 2.0, false);
+        c.set_transform(&(translation(-4.0, -1.0, 4.0) * scaling(0.5, 1.0, 0.5)));
+        let mut g = Group::new();
+        g.add_child(Box::new(s));
+        g.add_child(Box::new(c));
+
+        let b = g.bounds();
+
+        assert_eq!(b.min, Tuple::point(-4.5, -3.0, -5.0));
+        assert_eq!(b.max, Tuple::point(4.0, 7.0, 4.5));
+    }
+
+    #[test]
+    fn ray_missing_bounds_skips_children() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Sphere::unit()));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        assert!(g.local_intersect(&r).is_empty());
+    }
+
+    fn sphere_at(x: f64) -> Box<dyn Shape> {
+        let mut s = Sphere::unit();
+        s.set_transform(&translation(x, 0.0, 0.0));
+        Box::new(s)
+    }
+
+    #[test]
+    fn divide_splits_children_into_subgroups() {
+        let mut g = Group::new();
+        g.add_child(sphere_at(-2.0));
+        g.add_child(sphere_at(2.0));
+        g.add_child(Box::new(Sphere::unit()));
+
+        g.divide(1);
+
+        // The left half holds the spheres at -2 and 0, which is split once more.
+        assert_eq!(g.len(), 2);
+        let r = Ray::new(Tuple::point(-2.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = r.intersect(&g);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].time, 4.0);
+    }
+
+    #[test]
+    fn divide_leaves_small_groups_alone() {
+        let mut g = Group::new();
+        g.add_child(sphere_at(-2.0));
+        g.add_child(sphere_at(2.0));
+
+        g.divide(2);
+
+        assert_eq!(g.len(), 2);
+        assert!(same_shape(xs_object(&g, -2.0), g.children()[0].as_ref()));
+    }
+
+    fn xs_object(g: &Group, x: f64) -> &dyn Shape {
+        let r = Ray::new(Tuple::point(x, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        r.intersect(g)[0].object
+    }
+
+    #[test]
+    fn divide_keeps_unbounded_children_at_the_top() {
+        let mut g = Group::new();
+        g.add_child(Box::new(Plane::new()));
+        g.add_child(sphere_at(-2.0));
+        g.add_child(sphere_at(2.0));
+
+        g.divide(1);
+
+        assert_eq!(g.len(), 3);
+        assert!(!g.children()[0].bounds().is_finite());
+    }
+
+    #[test]
+    fn divide_keeps_composed_transforms_intact() {
+        let mut g = Group::new();
+        g.set_transform(&scaling(2.0, 2.0, 2.0));
+        for n in 0..10 {
+            let x = n as f64;
+            g.add_child(Box::new(Triangle::new(
+                Tuple::point(x, 1.0, 0.0),
+                Tuple::point(x - 0.5, 0.0, 0.0),
+                Tuple::point(x + 0.5, 0.0, 0.0),
+            )));
+        }
+
+        g.divide(2);
+
+        let r = Ray::new(Tuple::point(14.0, 0.5, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = r.intersect(&g);
+        assert_eq!(xs.len(), 1);
+        let comps = xs[0].prepare_computations(&r);
+        assert_eq!(comps.point, Tuple::point(14.0, 0.5, 0.0));
+        assert_eq!(comps.normal_vector, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn group_includes_its_descendants() {
+        let mut inner = Group::new();
+        inner.add_child(Box::new(Sphere::unit()));
+        let mut g = Group::new();
+        g.add_child(Box::new(inner));
+        let outsider = Sphere::unit();
+
+        let s = xs_object(&g, 0.0);
+
+        assert!(g.includes(s));
+        assert!(!g.includes(&outsider));
+    }
+}
@@ -1,47 +1,183 @@
-use crate::sphere::Sphere;
+use crate::ray::Ray;
+use crate::shape::{same_shape, Shape};
+use crate::tuple::Tuple;
 use crate::utils;
-use std::any::Any;
 use std::ops::Index;
 
-#[derive(Debug, Clone)]
-pub struct Intersection {
+#[derive(Debug, Clone, Copy)]
+pub struct Intersection<'a> {
+    pub time: f64,
+    pub object: &'a dyn Shape,
+    /// Where on the surface the hit landed, for shapes that parameterize it (e.g. triangles).
+    pub u: f64,
+    pub v: f64,
+}
+
+/// The state of the world at an intersection, precomputed once and reused for shading.
+#[derive(Debug)]
+pub struct Computations<'a> {
     pub time: f64,
-    pub object: Sphere, // TODO: Make this an object that implements Intersect
+    pub object: &'a dyn Shape,
+    pub point: Tuple,
+    /// The hit point nudged slightly along the normal, used as the origin of shadow rays.
+    pub over_point: Tuple,
+    /// The hit point nudged slightly below the surface, used as the origin of refracted rays.
+    pub under_point: Tuple,
+    pub eye_vector: Tuple,
+    pub normal_vector: Tuple,
+    /// The incoming ray mirrored around the normal, i.e. the direction of the reflected ray.
+    pub reflect_vector: Tuple,
+    pub inside: bool,
+    /// The refractive indices of the materials the ray leaves and enters at the hit.
+    pub n1: f64,
+    pub n2: f64,
 }
 
-impl PartialEq for Intersection {
+impl<'a> Intersection<'a> {
+    pub fn new(time: f64, object: &'a dyn Shape) -> Self {
+        Self::with_uv(time, object, 0.0, 0.0)
+    }
+
+    pub fn with_uv(time: f64, object: &'a dyn Shape, u: f64, v: f64) -> Self {
+        Self { time, object, u, v }
+    }
+
+    /// Prepares the hit as if it were the only intersection along the ray, i.e. for a hit
+    /// from vacuum into the object and back.
+    pub fn prepare_computations(&self, ray: &Ray) -> Computations<'a> {
+        self.prepare_computations_with(ray, &Intersections::new(vec![*self]))
+    }
+
+    /// Prepares the hit, given all intersections along the ray. Those are needed to tell
+    /// which objects the ray is inside at the hit, and hence which materials it passes between.
+    pub fn prepare_computations_with(&self, ray: &Ray, xs: &Intersections<'a>) -> Computations<'a> {
+        let point = ray.position(self.time);
+        let eye_vector = -ray.direction;
+        let mut normal_vector = self.object.normal_at_hit(&point, self);
+
+        // If the normal points away from the eye, we are inside the object.
+        let inside = normal_vector.dot(&eye_vector) < 0.0;
+        if inside {
+            normal_vector = -normal_vector;
+        }
+        let over_point = point + normal_vector * utils::SURFACE_EPSILON;
+        let under_point = point - normal_vector * utils::SURFACE_EPSILON;
+        let reflect_vector = ray.direction.reflect(&normal_vector);
+        let (n1, n2) = self.refractive_indices(xs);
+
+        Computations {
+            time: self.time,
+            object: self.object,
+            point,
+            over_point,
+            under_point,
+            eye_vector,
+            normal_vector,
+            reflect_vector,
+            inside,
+            n1,
+            n2,
+        }
+    }
+
+    /// Walks along the sorted intersections up to this hit, keeping track of the objects the
+    /// ray is inside of. The innermost one before and after the hit gives n1 and n2.
+    fn refractive_indices(&self, xs: &Intersections<'a>) -> (f64, f64) {
+        let mut containers: Vec<&dyn Shape> = vec![];
+        let innermost_index = |containers: &Vec<&dyn Shape>| {
+            containers
+                .last()
+                .map_or(1.0, |object| object.get_material().refractive_index)
+        };
+        let mut n1 = 1.0;
+
+        for i in &xs.objects {
+            if i == self {
+                n1 = innermost_index(&containers);
+            }
+
+            match containers.iter().position(|&c| same_shape(c, i.object)) {
+                Some(index) => {
+                    containers.remove(index);
+                }
+                None => containers.push(i.object),
+            }
+
+            if i == self {
+                return (n1, innermost_index(&containers));
+            }
+        }
+
+        (n1, 1.0)
+    }
+}
+
+impl Computations<'_> {
+    /// The Schlick approximation of the Fresnel effect: the fraction of light that is
+    /// reflected rather than refracted at the hit.
+    pub fn schlick(&self) -> f64 {
+        let mut cos = self.eye_vector.dot(&self.normal_vector);
+
+        // Total internal reflection can only occur when leaving the denser material.
+        if self.n1 > self.n2 {
+            let n_ratio = self.n1 / self.n2;
+            let sin2_t = n_ratio * n_ratio * (1.0 - cos * cos);
+            if sin2_t > 1.0 {
+                return 1.0;
+            }
+            cos = (1.0 - sin2_t).sqrt();
+        }
+
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
+}
+
+impl PartialEq for Intersection<'_> {
     fn eq(&self, other: &Self) -> bool {
         if (self.time - other.time).abs() > utils::F64_ERROR_MARGIN {
             return false;
         }
 
-        if self.object.type_id() != other.object.type_id() {
-            return false;
-        };
-
-        true
+        same_shape(self.object, other.object)
     }
 }
 
-#[derive(Debug)]
-pub struct Intersections {
-    pub objects: Vec<Intersection>,
+#[derive(Debug, Clone)]
+pub struct Intersections<'a> {
+    pub objects: Vec<Intersection<'a>>,
 }
 
-impl Index<usize> for Intersections {
-    type Output = Intersection;
+impl<'a> Index<usize> for Intersections<'a> {
+    type Output = Intersection<'a>;
 
     fn index(&self, index: usize) -> &Self::Output {
         &self.objects[index]
     }
 }
 
-impl Intersections {
+impl<'a> Intersections<'a> {
+    /// Collects the given intersections, sorted by time.
+    pub fn new(objects: Vec<Intersection<'a>>) -> Self {
+        let mut intersections = Self { objects };
+        intersections.sort();
+        intersections
+    }
+
     pub fn len(&self) -> usize {
         self.objects.len()
     }
 
-    pub fn get_hit(&self) -> Option<Intersection> {
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    pub fn sort(&mut self) {
+        self.objects
+            .sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+    }
+
+    pub fn get_hit(&self) -> Option<Intersection<'a>> {
         let min_intersect = self
             .objects
             .iter()
@@ -53,7 +189,7 @@ impl Intersections {
                 if i.time < 0.0 {
                     None
                 } else {
-                    Some(i.clone())
+                    Some(*i)
                 }
             }
         }
@@ -63,55 +199,234 @@ impl Intersections {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plane::Plane;
     use crate::sphere::Sphere;
+    use crate::transformations::{scaling, translation};
+    use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
 
     #[test]
     fn intersection_stores_time_and_object() {
-        let i = Intersection {
-            time: 1.3,
-            object: Sphere::unit(),
-        };
+        let s = Sphere::unit();
+        let i = Intersection::new(1.3, &s);
 
         assert_eq!(i.time, 1.3);
-        assert_eq!(i.object, Sphere::unit());
+        assert!(same_shape(i.object, &s));
     }
 
     #[test]
     fn intersection_aggregates_are_accessible() {
-        let i1 = Intersection {
-            time: 1.3,
-            object: Sphere::unit(),
-        };
-        let i2 = Intersection {
-            time: 1.4,
-            object: Sphere::unit(),
-        };
+        let s = Sphere::unit();
+        let i1 = Intersection::new(1.3, &s);
+        let i2 = Intersection::new(1.4, &s);
 
         let i = Intersections {
-            objects: vec![i1.clone(), i2.clone()],
+            objects: vec![i1, i2],
         };
 
         assert_eq!(i.objects[0], i1);
         assert_eq!(i.objects[1], i2);
     }
 
+    #[test]
+    fn precompute_state_of_intersection() {
+        let s = Sphere::unit();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, &s);
+
+        let comps = i.prepare_computations(&r);
+
+        assert_eq!(comps.time, i.time);
+        assert!(same_shape(comps.object, i.object));
+        assert_eq!(comps.point, Tuple::point(0.0, 0.0, -1.0));
+        assert_eq!(comps.eye_vector, Tuple::vector(0.0, 0.0, -1.0));
+        assert_eq!(comps.normal_vector, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn hit_when_intersection_occurs_on_outside() {
+        let s = Sphere::unit();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, &s);
+
+        let comps = i.prepare_computations(&r);
+
+        assert!(!comps.inside);
+    }
+
+    #[test]
+    fn hit_when_intersection_occurs_on_inside() {
+        let s = Sphere::unit();
+        let r = Ray::new(Tuple::origin(), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(1.0, &s);
+
+        let comps = i.prepare_computations(&r);
+
+        assert_eq!(comps.point, Tuple::point(0.0, 0.0, 1.0));
+        assert_eq!(comps.eye_vector, Tuple::vector(0.0, 0.0, -1.0));
+        assert!(comps.inside);
+        // The normal is inverted, since it would otherwise point away from the eye.
+        assert_eq!(comps.normal_vector, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn hit_should_offset_the_point() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let mut shape = Sphere::unit();
+        shape.set_transform(&translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+
+        let comps = i.prepare_computations(&r);
+
+        assert!(comps.over_point.z < -utils::SURFACE_EPSILON / 2.0);
+        assert!(comps.point.z > comps.over_point.z);
+    }
+
+    #[test]
+    fn precompute_reflection_vector() {
+        let shape = Plane::new();
+        let r = Ray::new(
+            Tuple::point(0.0, 1.0, -1.0),
+            Tuple::vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        );
+        let i = Intersection::new(SQRT_2, &shape);
+
+        let comps = i.prepare_computations(&r);
+
+        assert_eq!(
+            comps.reflect_vector,
+            Tuple::vector(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2)
+        );
+    }
+
+    #[test]
+    fn find_n1_and_n2_at_various_intersections() {
+        let mut a = Sphere::glass();
+        a.set_transform(&scaling(2.0, 2.0, 2.0));
+        let mut b = Sphere::glass();
+        b.set_transform(&translation(0.0, 0.0, -0.25));
+        let mut c = Sphere::glass();
+        c.set_transform(&translation(0.0, 0.0, 0.25));
+        for (sphere, index) in [(&mut a, 1.5), (&mut b, 2.0), (&mut c, 2.5)] {
+            let mut material = sphere.get_material().clone();
+            material.refractive_index = index;
+            sphere.set_material(&material);
+        }
+        let r = Ray::new(Tuple::point(0.0, 0.0, -4.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(2.0, &a),
+            Intersection::new(2.75, &b),
+            Intersection::new(3.25, &c),
+            Intersection::new(4.75, &b),
+            Intersection::new(5.25, &c),
+            Intersection::new(6.0, &a),
+        ]);
+        let expected = [
+            (1.0, 1.5),
+            (1.5, 2.0),
+            (2.0, 2.5),
+            (2.5, 2.5),
+            (2.5, 1.5),
+            (1.5, 1.0),
+        ];
+
+        for (index, (n1, n2)) in expected.into_iter().enumerate() {
+            let comps = xs[index].prepare_computations_with(&r, &xs);
+
+            assert_eq!(comps.n1, n1);
+            assert_eq!(comps.n2, n2);
+        }
+    }
+
+    #[test]
+    fn under_point_is_below_the_surface() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let mut shape = Sphere::glass();
+        shape.set_transform(&translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+
+        let comps = i.prepare_computations(&r);
+
+        assert!(comps.under_point.z > utils::SURFACE_EPSILON / 2.0);
+        assert!(comps.point.z < comps.under_point.z);
+    }
+
+    #[test]
+    fn schlick_under_total_internal_reflection() {
+        let shape = Sphere::glass();
+        let r = Ray::new(
+            Tuple::point(0.0, 0.0, FRAC_1_SQRT_2),
+            Tuple::vector(0.0, 1.0, 0.0),
+        );
+        let xs = Intersections::new(vec![
+            Intersection::new(-FRAC_1_SQRT_2, &shape),
+            Intersection::new(FRAC_1_SQRT_2, &shape),
+        ]);
+
+        let comps = xs[1].prepare_computations_with(&r, &xs);
+
+        assert_eq!(comps.schlick(), 1.0);
+    }
+
+    #[test]
+    fn schlick_with_perpendicular_viewing_angle() {
+        let shape = Sphere::glass();
+        let r = Ray::new(Tuple::origin(), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(-1.0, &shape),
+            Intersection::new(1.0, &shape),
+        ]);
+
+        let comps = xs[1].prepare_computations_with(&r, &xs);
+
+        assert!((comps.schlick() - 0.04).abs() < utils::F64_ERROR_MARGIN);
+    }
+
+    #[test]
+    fn schlick_with_small_angle_and_n2_greater_than_n1() {
+        let shape = Sphere::glass();
+        let r = Ray::new(Tuple::point(0.0, 0.99, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = Intersections::new(vec![Intersection::new(1.8589, &shape)]);
+
+        let comps = xs[0].prepare_computations_with(&r, &xs);
+
+        // The hit time is rounded, so the result only matches to a few decimals.
+        assert!((comps.schlick() - 0.48873).abs() < 1.0E-5);
+    }
+
+    #[test]
+    fn intersections_with_different_objects_are_not_equal() {
+        let s1 = Sphere::unit();
+        let s2 = Sphere::unit();
+
+        assert_ne!(Intersection::new(1.0, &s1), Intersection::new(1.0, &s2));
+    }
+
+    #[test]
+    fn new_intersections_are_sorted_by_time() {
+        let s = Sphere::unit();
+        let xs = Intersections::new(vec![
+            Intersection::new(2.0, &s),
+            Intersection::new(-1.0, &s),
+            Intersection::new(0.5, &s),
+        ]);
+
+        assert_eq!(xs[0].time, -1.0);
+        assert_eq!(xs[1].time, 0.5);
+        assert_eq!(xs[2].time, 2.0);
+    }
+
     mod hits {
         use crate::intersection::{Intersection, Intersections};
         use crate::sphere::Sphere;
 
         #[test]
         fn intersections_yields_hit() {
-            let i1 = Intersection {
-                time: 1.0,
-                object: Sphere::unit(),
-            };
-            let i2 = Intersection {
-                time: 2.0,
-                object: Sphere::unit(),
-            };
+            let s = Sphere::unit();
+            let i1 = Intersection::new(1.0, &s);
+            let i2 = Intersection::new(2.0, &s);
 
             let i = Intersections {
-                objects: vec![i1.clone(), i2],
+                objects: vec![i1, i2],
             };
 
             let hit = i.get_hit().unwrap();
@@ -121,17 +436,12 @@ mod tests {
 
         #[test]
         fn intersections_yields_hit_some_negative() {
-            let i1 = Intersection {
-                time: -1.0,
-                object: Sphere::unit(),
-            };
-            let i2 = Intersection {
-                time: 2.0,
-                object: Sphere::unit(),
-            };
+            let s = Sphere::unit();
+            let i1 = Intersection::new(-1.0, &s);
+            let i2 = Intersection::new(2.0, &s);
 
             let i = Intersections {
-                objects: vec![i1, i2.clone()],
+                objects: vec![i1, i2],
             };
 
             let hit = i.get_hit().unwrap();
@@ -141,17 +451,12 @@ mod tests {
 
         #[test]
         fn intersections_yields_hit_all_negative() {
-            let i1 = Intersection {
-                time: -1.0,
-                object: Sphere::unit(),
-            };
-            let i2 = Intersection {
-                time: -2.0,
-                object: Sphere::unit(),
-            };
+            let s = Sphere::unit();
+            let i1 = Intersection::new(-1.0, &s);
+            let i2 = Intersection::new(-2.0, &s);
 
             let i = Intersections {
-                objects: vec![i1, i2.clone()],
+                objects: vec![i1, i2],
             };
 
             let hit = i.get_hit();
@@ -160,26 +465,15 @@ mod tests {
 
         #[test]
         fn intersection_hits_lowest_nonnegative() {
-            let i1 = Intersection {
-                time: 5.0,
-                object: Sphere::unit(),
-            };
-            let i2 = Intersection {
-                time: 7.0,
-                object: Sphere::unit(),
-            };
+            let s = Sphere::unit();
+            let i1 = Intersection::new(5.0, &s);
+            let i2 = Intersection::new(7.0, &s);
 
-            let i3 = Intersection {
-                time: -3.0,
-                object: Sphere::unit(),
-            };
-            let i4 = Intersection {
-                time: 2.0,
-                object: Sphere::unit(),
-            };
+            let i3 = Intersection::new(-3.0, &s);
+            let i4 = Intersection::new(2.0, &s);
 
             let i = Intersections {
-                objects: vec![i1, i2, i3, i4.clone()],
+                objects: vec![i1, i2, i3, i4],
             };
 
             let hit = i.get_hit().unwrap();
@@ -1,11 +1,101 @@
 use crate::color::Color;
 use crate::material::Material;
+use crate::rng::Rng;
+use crate::shape::Shape;
 use crate::tuple::Tuple;
+use crate::utils;
+use crate::world::World;
+use std::fmt::Debug;
 
-#[derive(Clone, Copy)]
+/// Where one sample of a light lies as seen from a point being shaded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightSample {
+    /// The unit vector from the point towards the light.
+    pub direction: Tuple,
+    /// How far the light is along `direction`; infinite for lights with no position.
+    pub distance: f64,
+}
+
+impl LightSample {
+    /// The sample at `position`, seen from `point`.
+    pub fn towards(position: &Tuple, point: &Tuple) -> Self {
+        let point_to_light = *position - *point;
+        LightSample {
+            direction: point_to_light.norm(),
+            distance: point_to_light.abs(),
+        }
+    }
+}
+
+/// How a light dims with the distance from it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Attenuation {
+    /// The light is equally bright at any distance.
+    None,
+    /// The light falls off in proportion to the distance, to half its intensity at `radius`.
+    Linear { radius: f64 },
+    /// The light falls off with the square of the distance, as real light does, to a quarter
+    /// of its intensity at `radius`. The radius keeps it from blowing up right next to the
+    /// light.
+    InverseSquare { radius: f64 },
+}
+
+impl Attenuation {
+    /// The fraction of the light left after it has travelled `distance`.
+    pub fn factor(&self, distance: f64) -> f64 {
+        match self {
+            Attenuation::None => 1.0,
+            Attenuation::Linear { radius } => 1.0 / (1.0 + distance / radius),
+            Attenuation::InverseSquare { radius } => 1.0 / (1.0 + distance / radius).powi(2),
+        }
+    }
+
+    /// Panics unless the radius, if there is one, is positive. At a radius of zero the light
+    /// would be dark everywhere but at the light itself, where the factor is 0 / 0.
+    fn check_radius(&self) {
+        if let Attenuation::Linear { radius } | Attenuation::InverseSquare { radius } = self {
+            assert!(
+                *radius > 0.0,
+                "the attenuation radius of a light must be positive"
+            );
+        }
+    }
+}
+
+pub trait Light: Debug {
+    fn intensity(&self) -> Color;
+
+    fn attenuation(&self) -> Attenuation {
+        Attenuation::None
+    }
+
+    /// The parts of the light that shading and shadow rays from `point` aim at. Lights
+    /// without an extent have just the one.
+    fn samples(&self, point: &Tuple) -> Vec<LightSample>;
+
+    /// How strongly the light shines towards `point`, from 0 to 1, for lights that do not
+    /// shine equally in every direction.
+    fn falloff(&self, _point: &Tuple) -> f64 {
+        1.0
+    }
+
+    /// How much of the light reaches `point`, from 0 when it is fully in shadow to 1.
+    fn intensity_at(&self, point: &Tuple, world: &World) -> f64 {
+        let samples = self.samples(point);
+        let visible = samples
+            .iter()
+            .filter(|sample| !world.is_shadowed_along(point, &sample.direction, sample.distance))
+            .count();
+
+        visible as f64 / samples.len() as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct PointLight {
     position: Tuple,
     intensity: Color,
+    attenuation: Attenuation,
 }
 
 impl PointLight {
@@ -13,60 +103,697 @@ impl PointLight {
         Self {
             position,
             intensity,
+            attenuation: Attenuation::None,
+        }
+    }
+
+    pub fn position(&self) -> Tuple {
+        self.position
+    }
+
+    pub fn set_attenuation(&mut self, attenuation: Attenuation) {
+        attenuation.check_radius();
+        self.attenuation = attenuation;
+    }
+}
+
+impl Light for PointLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    fn samples(&self, point: &Tuple) -> Vec<LightSample> {
+        vec![LightSample::towards(&self.position, point)]
+    }
+}
+
+/// A point light that only shines within a cone. Points inside the inner angle get the full
+/// light, which then fades out smoothly towards the outer angle.
+#[derive(Debug, Clone, Copy)]
+pub struct SpotLight {
+    position: Tuple,
+    direction: Tuple,
+    /// Cosines of the half-angles of the two cones, so the falloff can be worked out from a
+    /// dot product.
+    cos_inner: f64,
+    cos_outer: f64,
+    intensity: Color,
+    attenuation: Attenuation,
+}
+
+impl SpotLight {
+    /// A spot light at `position` aimed along `direction`. The angles are measured from the
+    /// direction to the edge of each cone, in radians.
+    pub fn new(
+        position: Tuple,
+        direction: Tuple,
+        inner_angle: f64,
+        outer_angle: f64,
+        intensity: Color,
+    ) -> Self {
+        assert!(
+            inner_angle <= outer_angle,
+            "the inner cone of a spot light must fit inside the outer one"
+        );
+
+        Self {
+            position,
+            direction: direction.norm(),
+            cos_inner: inner_angle.cos(),
+            cos_outer: outer_angle.cos(),
+            intensity,
+            attenuation: Attenuation::None,
+        }
+    }
+
+    pub fn position(&self) -> Tuple {
+        self.position
+    }
+
+    pub fn direction(&self) -> Tuple {
+        self.direction
+    }
+
+    pub fn set_attenuation(&mut self, attenuation: Attenuation) {
+        attenuation.check_radius();
+        self.attenuation = attenuation;
+    }
+}
+
+impl Light for SpotLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    fn samples(&self, point: &Tuple) -> Vec<LightSample> {
+        vec![LightSample::towards(&self.position, point)]
+    }
+
+    fn falloff(&self, point: &Tuple) -> f64 {
+        let light_to_point = *point - self.position;
+        let distance = light_to_point.abs();
+        // At the light itself there is no direction to measure, so it shines in full.
+        if distance <= utils::F64_ERROR_MARGIN {
+            return 1.0;
+        }
+
+        let cos_angle = (light_to_point / distance).dot(&self.direction);
+
+        if cos_angle >= self.cos_inner {
+            1.0
+        } else if cos_angle <= self.cos_outer {
+            0.0
+        } else {
+            smoothstep((cos_angle - self.cos_outer) / (self.cos_inner - self.cos_outer))
+        }
+    }
+}
+
+/// Eases `t` in [0, 1] so the light has no visible edge where the falloff begins or ends.
+fn smoothstep(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// A light so far away that its rays arrive in parallel, such as the sun. It has a direction
+/// but no position, so nothing can lie behind it and every shadow ray runs to infinity.
+#[derive(Debug, Clone, Copy)]
+pub struct DirectionalLight {
+    /// The way the light travels, from the light towards the scene.
+    direction: Tuple,
+    intensity: Color,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Tuple, intensity: Color) -> Self {
+        Self {
+            direction: direction.norm(),
+            intensity,
         }
     }
+
+    pub fn direction(&self) -> Tuple {
+        self.direction
+    }
+}
+
+impl Light for DirectionalLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, _point: &Tuple) -> Vec<LightSample> {
+        vec![LightSample {
+            direction: -self.direction,
+            distance: f64::INFINITY,
+        }]
+    }
+}
+
+/// A rectangular light, divided into a grid of cells that are each sampled once. Points that
+/// see only some of the cells lie in the penumbra, which gives soft shadows.
+#[derive(Debug, Clone, Copy)]
+pub struct AreaLight {
+    corner: Tuple,
+    /// One cell along the first edge.
+    uvec: Tuple,
+    usteps: usize,
+    /// One cell along the second edge.
+    vvec: Tuple,
+    vsteps: usize,
+    intensity: Color,
+    /// The seed for sampling each cell at a random spot. Without jitter every cell is
+    /// sampled at its center, which shows up as banding in the penumbra.
+    jitter: Option<u64>,
+}
+
+impl AreaLight {
+    /// A light spanning the parallelogram from `corner` along the edges `full_uvec` and
+    /// `full_vvec`, split into `usteps` by `vsteps` cells.
+    pub fn new(
+        corner: Tuple,
+        full_uvec: Tuple,
+        usteps: usize,
+        full_vvec: Tuple,
+        vsteps: usize,
+        intensity: Color,
+    ) -> Self {
+        Self {
+            corner,
+            uvec: full_uvec / usteps as f64,
+            usteps,
+            vvec: full_vvec / vsteps as f64,
+            vsteps,
+            intensity,
+            jitter: None,
+        }
+    }
+
+    /// Samples each cell at a random spot instead of its center. The spots depend only on
+    /// `seed` and the point being shaded, so a render comes out the same every time.
+    pub fn set_jitter(&mut self, seed: u64) {
+        self.jitter = Some(seed);
+    }
+
+    /// The center of the light.
+    pub fn position(&self) -> Tuple {
+        self.corner
+            + self.uvec * (self.usteps as f64 / 2.0)
+            + self.vvec * (self.vsteps as f64 / 2.0)
+    }
+
+    /// The center of the cell `u` along the first edge and `v` along the second.
+    pub fn point_on_light(&self, u: usize, v: usize) -> Tuple {
+        self.point_in_cell(u, v, 0.5, 0.5)
+    }
+
+    /// The point at (`du`, `dv`), each in [0, 1), within the cell `u` along the first edge and
+    /// `v` along the second.
+    fn point_in_cell(&self, u: usize, v: usize, du: f64, dv: f64) -> Tuple {
+        self.corner + self.uvec * (u as f64 + du) + self.vvec * (v as f64 + dv)
+    }
+
+    /// One point in every cell, as seen when shading `point`.
+    fn points_on_light(&self, point: &Tuple) -> Vec<Tuple> {
+        let mut rng = self.jitter.map(|seed| jitter_rng(seed, point));
+
+        (0..self.vsteps)
+            .flat_map(|v| (0..self.usteps).map(move |u| (u, v)))
+            .map(|(u, v)| match &mut rng {
+                Some(rng) => self.point_in_cell(u, v, rng.next_f64(), rng.next_f64()),
+                None => self.point_on_light(u, v),
+            })
+            .collect()
+    }
+}
+
+/// A generator for jittering the light as seen from `point`. Shading and shadow rays from the
+/// same point then agree on where the samples are, and the result does not depend on what
+/// was traced before.
+fn jitter_rng(seed: u64, point: &Tuple) -> Rng {
+    let mut mixer = Rng::new(seed);
+    for coordinate in [point.x, point.y, point.z] {
+        mixer = Rng::new(mixer.next_u64() ^ coordinate.to_bits());
+    }
+    Rng::new(mixer.next_u64())
+}
+
+impl Light for AreaLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, point: &Tuple) -> Vec<LightSample> {
+        self.points_on_light(point)
+            .iter()
+            .map(|position| LightSample::towards(position, point))
+            .collect()
+    }
 }
 
+/// The Phong shading of `point` on `object` under `lights`, each paired with the fraction of
+/// it that reaches the point, as given by `Light::intensity_at`. The `material` is passed
+/// separately so it can be tried out without attaching it to a shape first.
+///
+/// Ambient light stands for light bounced around the whole scene, so it is counted once, and
+/// every light adds its own diffuse and specular term on top.
 pub fn lighting(
     material: &Material,
-    light: &PointLight,
+    object: &dyn Shape,
+    lights: &[(&dyn Light, f64)],
     point: &Tuple,
     eye_vector: &Tuple,
     normal_vector: &Tuple,
 ) -> Color {
-    let effective_color = material.color * light.intensity;
-    let light_vector = (light.position - *point).norm();
-    let ambient = effective_color * material.ambient;
+    let color = surface_color(material, object, point);
 
-    let light_dot_normal = light_vector.dot(normal_vector);
+    lights
+        .iter()
+        .filter(|(_, light_intensity)| *light_intensity > 0.0)
+        .fold(
+            color * material.ambient,
+            |total, (light, light_intensity)| {
+                total
+                    + direct_lighting(material, color, *light, point, eye_vector, normal_vector)
+                        * *light_intensity
+            },
+        )
+}
 
-    let (diffuse, specular) = {
-        if light_dot_normal < 0.0 {
-            let diffuse = 0.0;
-            let specular = 0.0;
+/// The color of the material at `point`, before any light falls on it.
+fn surface_color(material: &Material, object: &dyn Shape, point: &Tuple) -> Color {
+    match &material.pattern {
+        Some(pattern) => pattern.pattern_at_shape(object, point),
+        None => material.color,
+    }
+}
 
-            (Color::black(), Color::black())
-        } else {
-            let diffuse = effective_color * material.diffuse * light_dot_normal;
-            let reflect_vector = (-light_vector).reflect(normal_vector);
-            let reflect_dot_eye = reflect_vector.dot(eye_vector);
+/// The diffuse and specular light that an unobstructed `light` casts on a surface of `color`
+/// at `point`, averaged over the samples of the light.
+fn direct_lighting(
+    material: &Material,
+    color: Color,
+    light: &dyn Light,
+    point: &Tuple,
+    eye_vector: &Tuple,
+    normal_vector: &Tuple,
+) -> Color {
+    let falloff = light.falloff(point);
+    if falloff == 0.0 {
+        return Color::black();
+    }
+    let attenuation = light.attenuation();
+    let samples = light.samples(point);
 
-            let specular = {
-                if reflect_dot_eye <= 0.0 {
-                    Color::black()
-                } else {
-                    let factor = reflect_dot_eye.powf(material.shininess);
-                    light.intensity * material.specular * factor
-                }
-            };
-            (diffuse, specular)
+    let total = samples.iter().fold(Color::black(), |total, sample| {
+        let light_vector = sample.direction;
+        let light_dot_normal = light_vector.dot(normal_vector);
+        if light_dot_normal < 0.0 {
+            return total;
         }
-    };
-    ambient + diffuse + specular
+
+        let intensity = light.intensity() * (falloff * attenuation.factor(sample.distance));
+
+        let diffuse = color * intensity * material.diffuse * light_dot_normal;
+        let reflect_vector = (-light_vector).reflect(normal_vector);
+        let reflect_dot_eye = reflect_vector.dot(eye_vector);
+
+        let specular = {
+            if reflect_dot_eye <= 0.0 {
+                Color::black()
+            } else {
+                let factor = reflect_dot_eye.powf(material.shininess);
+                intensity * material.specular * factor
+            }
+        };
+        total + diffuse + specular
+    });
+
+    total * (1.0 / samples.len() as f64)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::camera::{render, Camera};
+    use crate::plane::Plane;
+    use crate::sphere::Sphere;
+    use crate::transformations::{translation, view_transform};
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
 
     #[test]
     fn test_point_light_has_intensity_and_position() {
         let l = PointLight {
             intensity: Color::new(0.0, 0.0, 0.0),
             position: Tuple::origin(),
+            attenuation: Attenuation::None,
         };
 
         assert_eq!(l.intensity, Color::new(0.0, 0.0, 0.0));
         assert_eq!(l.position, Tuple::origin());
     }
+
+    #[test]
+    fn point_light_intensity_at_is_all_or_nothing() {
+        let w = World::default();
+        let cases = [
+            (Tuple::point(0.0, 1.0001, 0.0), 1.0),
+            (Tuple::point(-1.0001, 0.0, 0.0), 1.0),
+            (Tuple::point(0.0, 0.0, -1.0001), 1.0),
+            (Tuple::point(0.0, 0.0, 1.0001), 0.0),
+            (Tuple::point(1.0001, 0.0, 0.0), 0.0),
+            (Tuple::point(0.0, -1.0001, 0.0), 0.0),
+            (Tuple::origin(), 0.0),
+        ];
+
+        for (point, intensity) in cases {
+            assert_eq!(w.lights[0].intensity_at(&point, &w), intensity);
+        }
+    }
+
+    #[test]
+    fn attenuation_factor_at_radius() {
+        let cases = [
+            (Attenuation::None, 1.0),
+            (Attenuation::Linear { radius: 4.0 }, 0.5),
+            (Attenuation::InverseSquare { radius: 4.0 }, 0.25),
+        ];
+
+        for (attenuation, factor) in cases {
+            assert_eq!(attenuation.factor(0.0), 1.0);
+            assert_eq!(attenuation.factor(4.0), factor);
+        }
+    }
+
+    #[test]
+    fn inverse_square_attenuation_quarters_light_at_double_distance() {
+        let attenuation = Attenuation::InverseSquare { radius: 0.001 };
+
+        let ratio = attenuation.factor(20.0) / attenuation.factor(10.0);
+
+        assert!((ratio - 0.25).abs() < 1.0E-4);
+    }
+
+    #[test]
+    #[should_panic(expected = "radius of a light must be positive")]
+    fn point_light_rejects_zero_attenuation_radius() {
+        let mut light = PointLight::new(Tuple::origin(), Color::new(1.0, 1.0, 1.0));
+
+        light.set_attenuation(Attenuation::Linear { radius: 0.0 });
+    }
+
+    #[test]
+    #[should_panic(expected = "radius of a light must be positive")]
+    fn spot_light_rejects_zero_attenuation_radius() {
+        let mut light = spot_light();
+
+        light.set_attenuation(Attenuation::InverseSquare { radius: 0.0 });
+    }
+
+    fn spot_light() -> SpotLight {
+        SpotLight::new(
+            Tuple::point(0.0, 0.0, -10.0),
+            Tuple::vector(0.0, 0.0, 2.0),
+            PI / 8.0,
+            PI / 4.0,
+            Color::new(1.0, 1.0, 1.0),
+        )
+    }
+
+    #[test]
+    fn spot_light_shines_fully_within_inner_cone() {
+        let light = spot_light();
+
+        assert_eq!(light.direction(), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(light.falloff(&Tuple::origin()), 1.0);
+        assert_eq!(light.falloff(&Tuple::point(0.0, 3.0, 0.0)), 1.0);
+    }
+
+    #[test]
+    fn spot_light_is_dark_outside_outer_cone() {
+        let light = spot_light();
+
+        assert_eq!(light.falloff(&Tuple::point(0.0, 11.0, 0.0)), 0.0);
+        assert_eq!(light.falloff(&Tuple::point(0.0, 0.0, -11.0)), 0.0);
+    }
+
+    #[test]
+    fn spot_light_shines_fully_at_its_own_position() {
+        let light = spot_light();
+
+        assert_eq!(light.falloff(&light.position()), 1.0);
+    }
+
+    #[test]
+    fn spot_light_fades_between_cones() {
+        let light = spot_light();
+        let falloffs: Vec<f64> = (0..=10)
+            .map(|n| light.falloff(&Tuple::point(0.0, 4.0 + 0.6 * n as f64, 0.0)))
+            .collect();
+
+        assert!(falloffs.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert!(falloffs[0] > 0.9);
+        assert!(falloffs[10] < 0.1);
+    }
+
+    #[test]
+    fn lighting_outside_spot_light_is_ambient() {
+        let light = spot_light();
+        let m = Material::default();
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+
+        let lit = lighting(
+            &m,
+            &Sphere::unit(),
+            &[(&light, 1.0)],
+            &Tuple::origin(),
+            &eye,
+            &normal,
+        );
+        let unlit = lighting(
+            &m,
+            &Sphere::unit(),
+            &[(&light, 1.0)],
+            &Tuple::point(0.0, 20.0, 0.0),
+            &eye,
+            &normal,
+        );
+
+        assert_eq!(lit, Color::new(1.9, 1.9, 1.9));
+        assert_eq!(unlit, Color::new(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn directional_light_comes_from_the_same_direction_everywhere() {
+        let light = DirectionalLight::new(Tuple::vector(0.0, -2.0, 0.0), Color::new(1.0, 1.0, 1.0));
+        let expected = vec![LightSample {
+            direction: Tuple::vector(0.0, 1.0, 0.0),
+            distance: f64::INFINITY,
+        }];
+
+        assert_eq!(light.samples(&Tuple::origin()), expected);
+        assert_eq!(light.samples(&Tuple::point(30.0, -7.0, 2.0)), expected);
+    }
+
+    #[test]
+    fn lighting_with_directional_light_does_not_depend_on_position() {
+        let light = DirectionalLight::new(Tuple::vector(0.0, 0.0, 1.0), Color::new(1.0, 1.0, 1.0));
+        let m = Material::default();
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+
+        for point in [Tuple::origin(), Tuple::point(50.0, -20.0, 1000.0)] {
+            let result = lighting(&m, &Sphere::unit(), &[(&light, 1.0)], &point, &eye, &normal);
+
+            assert_eq!(result, Color::new(1.9, 1.9, 1.9));
+        }
+    }
+
+    #[test]
+    fn directional_light_is_blocked_by_objects_at_any_distance() {
+        let mut w = World::new();
+        let mut far_sphere = Sphere::unit();
+        far_sphere.set_transform(&translation(0.0, 1000.0, 0.0));
+        w.add_object(Box::new(far_sphere));
+        let sun = DirectionalLight::new(Tuple::vector(0.0, -1.0, 0.0), Color::new(1.0, 1.0, 1.0));
+
+        assert_eq!(sun.intensity_at(&Tuple::origin(), &w), 0.0);
+        assert_eq!(sun.intensity_at(&Tuple::point(2.0, 0.0, 0.0), &w), 1.0);
+    }
+
+    #[test]
+    fn create_area_light() {
+        let light = AreaLight::new(
+            Tuple::origin(),
+            Tuple::vector(2.0, 0.0, 0.0),
+            4,
+            Tuple::vector(0.0, 0.0, 1.0),
+            2,
+            Color::new(1.0, 1.0, 1.0),
+        );
+
+        assert_eq!(light.uvec, Tuple::vector(0.5, 0.0, 0.0));
+        assert_eq!(light.vvec, Tuple::vector(0.0, 0.0, 0.5));
+        assert_eq!(light.samples(&Tuple::origin()).len(), 8);
+        assert_eq!(light.position(), Tuple::point(1.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn point_on_area_light_is_center_of_cell() {
+        let light = AreaLight::new(
+            Tuple::origin(),
+            Tuple::vector(2.0, 0.0, 0.0),
+            4,
+            Tuple::vector(0.0, 0.0, 1.0),
+            2,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        let cases = [
+            (0, 0, Tuple::point(0.25, 0.0, 0.25)),
+            (1, 0, Tuple::point(0.75, 0.0, 0.25)),
+            (0, 1, Tuple::point(0.25, 0.0, 0.75)),
+            (2, 0, Tuple::point(1.25, 0.0, 0.25)),
+            (3, 1, Tuple::point(1.75, 0.0, 0.75)),
+        ];
+
+        for (u, v, point) in cases {
+            assert_eq!(light.point_on_light(u, v), point);
+        }
+    }
+
+    #[test]
+    fn jittered_point_stays_within_its_cell() {
+        let mut light = AreaLight::new(
+            Tuple::origin(),
+            Tuple::vector(2.0, 0.0, 0.0),
+            4,
+            Tuple::vector(0.0, 0.0, 1.0),
+            2,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        light.set_jitter(3);
+
+        for n in 0..100 {
+            // The last cell, u = 3 and v = 1.
+            let point = light.points_on_light(&Tuple::point(n as f64, 5.0, 0.0))[7];
+            assert!((1.5..2.0).contains(&point.x));
+            assert!((0.5..1.0).contains(&point.z));
+        }
+    }
+
+    #[test]
+    fn jitter_is_determined_by_seed() {
+        let jittered = |seed| {
+            let mut light = AreaLight::new(
+                Tuple::origin(),
+                Tuple::vector(2.0, 0.0, 0.0),
+                4,
+                Tuple::vector(0.0, 0.0, 1.0),
+                2,
+                Color::new(1.0, 1.0, 1.0),
+            );
+            light.set_jitter(seed);
+            light.samples(&Tuple::origin())
+        };
+
+        assert_eq!(jittered(1), jittered(1));
+        assert_ne!(jittered(1), jittered(2));
+    }
+
+    #[test]
+    fn jittered_area_light_renders_the_same_every_time() {
+        let mut light = AreaLight::new(
+            Tuple::point(-1.0, 4.0, -1.0),
+            Tuple::vector(2.0, 0.0, 0.0),
+            4,
+            Tuple::vector(0.0, 0.0, 2.0),
+            4,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        light.set_jitter(0);
+        let mut w = World::new();
+        w.add_object(Box::new(Plane::new()));
+        let mut sphere = Sphere::unit();
+        sphere.set_transform(&translation(0.0, 1.0, 0.0));
+        w.add_object(Box::new(sphere));
+        w.add_light(Box::new(light));
+        let mut camera = Camera::new(20, 10, PI / 2.0);
+        camera.set_transform(&view_transform(
+            &Tuple::point(0.0, 5.0, -3.0),
+            &Tuple::origin(),
+            &Tuple::vector(0.0, 1.0, 0.0),
+        ));
+
+        let first = render(&camera, &w);
+        let second = render(&camera, &w);
+
+        assert_eq!(first.pixels, second.pixels);
+    }
+
+    #[test]
+    fn area_light_intensity_at_is_fraction_of_visible_cells() {
+        let w = World::default();
+        let light = AreaLight::new(
+            Tuple::point(-0.5, -0.5, -5.0),
+            Tuple::vector(1.0, 0.0, 0.0),
+            2,
+            Tuple::vector(0.0, 1.0, 0.0),
+            2,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        let cases = [
+            (Tuple::point(0.0, 0.0, 2.0), 0.0),
+            (Tuple::point(1.0, -1.0, 2.0), 0.25),
+            (Tuple::point(1.5, 0.0, 2.0), 0.5),
+            (Tuple::point(1.25, 1.25, 3.0), 0.75),
+            (Tuple::point(0.0, 0.0, -2.0), 1.0),
+        ];
+
+        for (point, intensity) in cases {
+            assert_eq!(light.intensity_at(&point, &w), intensity);
+        }
+    }
+
+    #[test]
+    fn lighting_samples_the_area_light() {
+        let light = AreaLight::new(
+            Tuple::point(-0.5, -0.5, -5.0),
+            Tuple::vector(1.0, 0.0, 0.0),
+            2,
+            Tuple::vector(0.0, 1.0, 0.0),
+            2,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        let shape = Sphere::unit();
+        let mut m = Material::default();
+        m.ambient = 0.1;
+        m.diffuse = 0.9;
+        m.specular = 0.0;
+        m.color = Color::new(1.0, 1.0, 1.0);
+        let eye = Tuple::point(0.0, 0.0, -5.0);
+        let cases = [
+            (Tuple::point(0.0, 0.0, -1.0), 0.9965048411651541),
+            (
+                Tuple::point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+                0.623186536971693,
+            ),
+        ];
+
+        for (point, expected) in cases {
+            let eye_vector = (eye - point).norm();
+            let normal = Tuple::vector(point.x, point.y, point.z);
+
+            let result = lighting(&m, &shape, &[(&light, 1.0)], &point, &eye_vector, &normal);
+
+            assert_eq!(result, Color::new(expected, expected, expected));
+        }
+    }
 }
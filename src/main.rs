@@ -1,27 +1,49 @@
+mod bounds;
+mod camera;
 mod canvas;
 mod clock;
 mod color;
+mod cone;
+mod csg;
+mod cube;
+mod cylinder;
+mod environment;
+mod group;
 mod image;
 mod intersection;
 mod lights;
 mod material;
 mod matrix;
-mod normals;
+mod noise;
+mod obj_file;
+mod pattern;
+mod plane;
 mod projectile;
 mod ray;
 mod renders;
+mod rng;
+mod sampling;
+mod shape;
 mod sphere;
+mod texture;
 mod transformations;
+mod triangle;
 mod tuple;
 mod utils;
+mod world;
 
-use crate::clock::clock;
-use crate::projectile::projectile;
-use crate::renders::{render_sphere, render_sphere_with_shading};
+use crate::renders::render_sphere_with_shading;
 
 fn main() {
-    // clock();
-    // projectile();
-    // render_sphere();
+    // clock::clock();
+    // projectile::projectile();
+    // renders::render_sphere();
+    // renders::render_sphere_with_three_point_lighting();
+    // renders::render_hexagon();
+    // renders::render_soft_shadows();
+    // renders::render_spot_light();
+    // renders::render_sunlight();
+    // renders::render_earth("../examples/textures/earth.jpg");
+    // renders::render_studio("../examples/textures/studio.hdr");
     render_sphere_with_shading()
 }
@@ -1,40 +1,99 @@
 use crate::color::Color;
+use crate::pattern::Pattern;
+use std::rc::Rc;
 
-#[derive(PartialEq, Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Material {
     pub color: Color,
+    /// When set, the pattern decides the color of the surface instead of `color`.
+    pub pattern: Option<Rc<dyn Pattern>>,
     pub ambient: f64,
     pub diffuse: f64,
     pub specular: f64,
     pub shininess: f64,
+    /// How much of the scene the surface mirrors, from 0 (matte) to 1 (a perfect mirror).
+    pub reflective: f64,
+    /// How much light passes through the surface, from 0 (opaque) to 1 (fully transparent).
+    pub transparency: f64,
+    /// How strongly light bends when entering the material: 1 for vacuum, about 1.33 for
+    /// water, 1.5 for glass and 2.42 for diamond.
+    pub refractive_index: f64,
 }
 
 impl Material {
     pub fn new(color: Color, ambient: f64, diffuse: f64, specular: f64, shininess: f64) -> Self {
         Self {
             color,
+            pattern: None,
             ambient,
             diffuse,
             specular,
             shininess,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
         }
     }
     pub fn default() -> Self {
         Self {
             color: Color::new(1.0, 1.0, 1.0),
+            pattern: None,
             ambient: 0.1,
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
         }
     }
 }
 
+/// Materials are equal when they look the same, which for patterns means sharing the very
+/// same pattern.
+impl PartialEq for Material {
+    fn eq(&self, other: &Self) -> bool {
+        let same_pattern = match (&self.pattern, &other.pattern) {
+            (None, None) => true,
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        };
+
+        same_pattern
+            && self.color == other.color
+            && self.ambient == other.ambient
+            && self.diffuse == other.diffuse
+            && self.specular == other.specular
+            && self.shininess == other.shininess
+            && self.reflective == other.reflective
+            && self.transparency == other.transparency
+            && self.refractive_index == other.refractive_index
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::lights::{lighting, PointLight};
+    use crate::lights::{lighting, Attenuation, PointLight, SpotLight};
+    use crate::pattern::Stripe;
+    use crate::sphere::Sphere;
     use crate::tuple::Tuple;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn default_material_is_not_reflective() {
+        let m = Material::default();
+
+        assert_eq!(m.reflective, 0.0);
+    }
+
+    #[test]
+    fn default_material_is_opaque_vacuum() {
+        let m = Material::default();
+
+        assert_eq!(m.transparency, 0.0);
+        assert_eq!(m.refractive_index, 1.0);
+    }
 
     #[test]
     fn lighting_eye_between_source_and_surface() {
@@ -44,11 +103,31 @@ mod tests {
         let normal = Tuple::vector(0.0, 0.0, -1.0);
         let l = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
-        let result = lighting(&m, &l, &pos, &eye, &normal);
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 1.0)], &pos, &eye, &normal);
 
         assert_eq!(result, Color::new(1.9, 1.9, 1.9))
     }
 
+    #[test]
+    fn lighting_counts_ambient_once_for_several_lights() {
+        let m = Material::default();
+        let pos = Tuple::origin();
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+        let l = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        let result = lighting(
+            &m,
+            &Sphere::unit(),
+            &[(&l, 1.0), (&l, 0.5), (&l, 0.0)],
+            &pos,
+            &eye,
+            &normal,
+        );
+
+        assert_eq!(result, Color::new(2.8, 2.8, 2.8))
+    }
+
     #[test]
     fn lighting_eye_offset_45_degrees() {
         let m = Material::default();
@@ -57,7 +136,7 @@ mod tests {
         let normal = Tuple::vector(0.0, 0.0, -1.0);
         let l = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
-        let result = lighting(&m, &l, &pos, &eye, &normal);
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 1.0)], &pos, &eye, &normal);
 
         assert_eq!(result, Color::new(1.0, 1.0, 1.0))
     }
@@ -70,7 +149,7 @@ mod tests {
         let normal = Tuple::vector(0.0, 0.0, -1.0);
         let l = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
-        let result = lighting(&m, &l, &pos, &eye, &normal);
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 1.0)], &pos, &eye, &normal);
 
         assert_eq!(
             result,
@@ -86,7 +165,7 @@ mod tests {
         let normal = Tuple::vector(0.0, 0.0, -1.0);
         let l = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
 
-        let result = lighting(&m, &l, &pos, &eye, &normal);
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 1.0)], &pos, &eye, &normal);
 
         assert_eq!(
             result,
@@ -102,8 +181,168 @@ mod tests {
         let normal = Tuple::vector(0.0, 0.0, -1.0);
         let l = PointLight::new(Tuple::point(0.0, 0.0, 10.0), Color::new(1.0, 1.0, 1.0));
 
-        let result = lighting(&m, &l, &pos, &eye, &normal);
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 1.0)], &pos, &eye, &normal);
 
         assert_eq!(result, Color::new(0.1, 0.1, 0.1))
     }
+
+    #[test]
+    fn lighting_surface_in_shadow() {
+        let m = Material::default();
+        let pos = Tuple::origin();
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+        let l = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 0.0)], &pos, &eye, &normal);
+
+        assert_eq!(result, Color::new(0.1, 0.1, 0.1))
+    }
+
+    #[test]
+    fn lighting_ambient_does_not_take_color_of_light() {
+        let m = Material::default();
+        let pos = Tuple::origin();
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+        let l = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 0.0, 0.0));
+
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 0.0)], &pos, &eye, &normal);
+
+        assert_eq!(result, Color::new(0.1, 0.1, 0.1))
+    }
+
+    #[test]
+    fn lighting_scales_direct_light_by_light_intensity() {
+        let mut m = Material::default();
+        m.ambient = 0.1;
+        m.diffuse = 0.9;
+        m.specular = 0.0;
+        m.color = Color::new(1.0, 1.0, 1.0);
+        let pos = Tuple::point(0.0, 0.0, -1.0);
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+        let l = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        for (intensity, expected) in [(1.0, 1.0), (0.5, 0.55), (0.0, 0.1)] {
+            let result = lighting(&m, &Sphere::unit(), &[(&l, intensity)], &pos, &eye, &normal);
+
+            assert_eq!(result, Color::new(expected, expected, expected));
+        }
+    }
+
+    #[test]
+    fn lighting_with_linear_attenuation() {
+        let m = Material::default();
+        let pos = Tuple::origin();
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+        let mut l = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        l.set_attenuation(Attenuation::Linear { radius: 10.0 });
+
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 1.0)], &pos, &eye, &normal);
+
+        assert_eq!(result, Color::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn lighting_with_inverse_square_attenuation() {
+        let m = Material::default();
+        let pos = Tuple::origin();
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+        let mut l = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        l.set_attenuation(Attenuation::InverseSquare { radius: 10.0 });
+
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 1.0)], &pos, &eye, &normal);
+
+        assert_eq!(result, Color::new(0.55, 0.55, 0.55))
+    }
+
+    #[test]
+    fn lighting_attenuation_leaves_ambient_alone() {
+        let m = Material::default();
+        let pos = Tuple::origin();
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+        let mut l = PointLight::new(Tuple::point(0.0, 0.0, 10.0), Color::new(1.0, 1.0, 1.0));
+        l.set_attenuation(Attenuation::InverseSquare { radius: 1.0 });
+
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 1.0)], &pos, &eye, &normal);
+
+        assert_eq!(result, Color::new(0.1, 0.1, 0.1))
+    }
+
+    #[test]
+    fn lighting_with_attenuated_spot_light() {
+        let m = Material::default();
+        let pos = Tuple::origin();
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+        let mut l = SpotLight::new(
+            Tuple::point(0.0, 0.0, -10.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            PI / 8.0,
+            PI / 4.0,
+            Color::new(1.0, 1.0, 1.0),
+        );
+        l.set_attenuation(Attenuation::Linear { radius: 10.0 });
+
+        let result = lighting(&m, &Sphere::unit(), &[(&l, 1.0)], &pos, &eye, &normal);
+
+        assert_eq!(result, Color::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn lighting_with_pattern_applied() {
+        let mut m = Material::default();
+        m.pattern = Some(Rc::new(Stripe::new(
+            Color::new(1.0, 1.0, 1.0),
+            Color::black(),
+        )));
+        m.ambient = 1.0;
+        m.diffuse = 0.0;
+        m.specular = 0.0;
+        let eye = Tuple::vector(0.0, 0.0, -1.0);
+        let normal = Tuple::vector(0.0, 0.0, -1.0);
+        let l = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+        let s = Sphere::unit();
+
+        let c1 = lighting(
+            &m,
+            &s,
+            &[(&l, 1.0)],
+            &Tuple::point(0.9, 0.0, 0.0),
+            &eye,
+            &normal,
+        );
+        let c2 = lighting(
+            &m,
+            &s,
+            &[(&l, 1.0)],
+            &Tuple::point(1.1, 0.0, 0.0),
+            &eye,
+            &normal,
+        );
+
+        assert_eq!(c1, Color::new(1.0, 1.0, 1.0));
+        assert_eq!(c2, Color::black());
+    }
+
+    #[test]
+    fn materials_compare_patterns_by_identity() {
+        let stripe = Rc::new(Stripe::new(Color::new(1.0, 1.0, 1.0), Color::black()));
+        let mut m1 = Material::default();
+        m1.pattern = Some(stripe.clone());
+        let mut m2 = Material::default();
+        m2.pattern = Some(stripe);
+        let mut m3 = Material::default();
+        m3.pattern = Some(Rc::new(Stripe::new(
+            Color::new(1.0, 1.0, 1.0),
+            Color::black(),
+        )));
+
+        assert_eq!(m1, m2);
+        assert_ne!(m1, m3);
+    }
 }
@@ -233,6 +233,7 @@ impl Mul<&Tuple> for &Matrix {
 impl Mul<Tuple> for &Matrix {
     type Output = Tuple;
 
+    #[allow(clippy::op_ref)]
     fn mul(self, rhs: Tuple) -> Self::Output {
         self * &rhs
     }
@@ -0,0 +1,145 @@
+use crate::rng::Rng;
+
+/// Gradient noise after Ken Perlin's improved noise, with a permutation shuffled from a seed.
+///
+/// The noise is smooth, zero at every integer lattice point, and stays within [-1, 1].
+#[derive(Debug, Clone)]
+pub struct Perlin {
+    /// The shuffled numbers 0..256, repeated once so lookups need not wrap.
+    permutation: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut table: Vec<usize> = (0..256).collect();
+        for i in (1..table.len()).rev() {
+            table.swap(i, rng.below(i + 1));
+        }
+
+        let permutation = table.iter().chain(table.iter()).copied().collect();
+        Perlin { permutation }
+    }
+
+    pub fn noise(&self, x: f64, y: f64, z: f64) -> f64 {
+        let p = &self.permutation;
+
+        // The unit cube containing the point, and where in that cube the point lies.
+        let (xi, yi, zi) = (lattice(x), lattice(y), lattice(z));
+        let (x, y, z) = (x - x.floor(), y - y.floor(), z - z.floor());
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        // Hash the coordinates of the eight corners of the cube.
+        let a = p[xi] + yi;
+        let aa = p[a] + zi;
+        let ab = p[a + 1] + zi;
+        let b = p[xi + 1] + yi;
+        let ba = p[b] + zi;
+        let bb = p[b + 1] + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad(p[aa], x, y, z), grad(p[ba], x - 1.0, y, z)),
+                lerp(
+                    u,
+                    grad(p[ab], x, y - 1.0, z),
+                    grad(p[bb], x - 1.0, y - 1.0, z),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(p[aa + 1], x, y, z - 1.0),
+                    grad(p[ba + 1], x - 1.0, y, z - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad(p[ab + 1], x, y - 1.0, z - 1.0),
+                    grad(p[bb + 1], x - 1.0, y - 1.0, z - 1.0),
+                ),
+            ),
+        )
+    }
+}
+
+/// The lattice cell of `coordinate`, wrapped to the size of the permutation table.
+fn lattice(coordinate: f64) -> usize {
+    (coordinate.floor() as i64).rem_euclid(256) as usize
+}
+
+/// Eases the coordinate within a cell, so the noise has no visible creases at cell borders.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// The dot product of the point with one of twelve gradient directions picked by `hash`.
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noise_vanishes_on_lattice_points() {
+        let perlin = Perlin::new(0);
+
+        for (x, y, z) in [(0.0, 0.0, 0.0), (1.0, 2.0, 3.0), (-4.0, 7.0, -300.0)] {
+            assert_eq!(perlin.noise(x, y, z), 0.0);
+        }
+    }
+
+    #[test]
+    fn noise_is_determined_by_seed() {
+        let a = Perlin::new(3);
+        let b = Perlin::new(3);
+        let c = Perlin::new(4);
+
+        assert_eq!(a.noise(0.3, 1.7, -2.2), b.noise(0.3, 1.7, -2.2));
+        assert_ne!(a.noise(0.3, 1.7, -2.2), c.noise(0.3, 1.7, -2.2));
+    }
+
+    #[test]
+    fn noise_is_bounded_and_varies() {
+        let perlin = Perlin::new(11);
+        let mut rng = Rng::new(5);
+        let samples: Vec<f64> = (0..1000)
+            .map(|_| {
+                let mut coordinate = || rng.next_f64() * 20.0 - 10.0;
+                perlin.noise(coordinate(), coordinate(), coordinate())
+            })
+            .collect();
+
+        assert!(samples.iter().all(|n| (-1.0..=1.0).contains(n)));
+        assert!(samples.iter().any(|n| *n > 0.2));
+        assert!(samples.iter().any(|n| *n < -0.2));
+    }
+
+    #[test]
+    fn noise_is_continuous() {
+        let perlin = Perlin::new(11);
+
+        let here = perlin.noise(2.5, 0.3, -1.2);
+        let nearby = perlin.noise(2.5 + 1.0E-6, 0.3, -1.2);
+
+        assert!((here - nearby).abs() < 1.0E-4);
+    }
+}
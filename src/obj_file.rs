@@ -0,0 +1,566 @@
+use crate::group::Group;
+use crate::shape::Shape;
+use crate::triangle::{SmoothTriangle, Triangle};
+use crate::tuple::Tuple;
+use std::fmt;
+use std::fs;
+
+/// A face of the model, smooth if the file gave normals for its vertices.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ObjTriangle {
+    Flat(Triangle),
+    Smooth(SmoothTriangle),
+}
+
+impl ObjTriangle {
+    pub fn into_shape(self) -> Box<dyn Shape> {
+        match self {
+            ObjTriangle::Flat(t) => Box::new(t),
+            ObjTriangle::Smooth(t) => Box::new(t),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ObjGroup {
+    pub name: String,
+    pub triangles: Vec<ObjTriangle>,
+}
+
+/// The contents of a Wavefront OBJ file. Faces that appear before any `g` statement
+/// end up in `default_group`.
+#[derive(Debug)]
+pub struct ObjFile {
+    pub vertices: Vec<Tuple>,
+    pub normals: Vec<Tuple>,
+    pub texture_coordinates: Vec<(f64, f64)>,
+    pub default_group: Vec<ObjTriangle>,
+    pub named_groups: Vec<ObjGroup>,
+    /// Line numbers (1-based) of statements the parser does not understand and skipped.
+    pub ignored_lines: Vec<usize>,
+}
+
+impl ObjFile {
+    fn new() -> Self {
+        Self {
+            vertices: vec![],
+            normals: vec![],
+            texture_coordinates: vec![],
+            default_group: vec![],
+            named_groups: vec![],
+            ignored_lines: vec![],
+        }
+    }
+
+    pub fn group(&self, name: &str) -> Option<&ObjGroup> {
+        self.named_groups.iter().find(|g| g.name == name)
+    }
+
+    /// Every triangle in the file, ready to be added to a world.
+    pub fn into_shapes(self) -> Vec<Box<dyn Shape>> {
+        self.default_group
+            .into_iter()
+            .chain(self.named_groups.into_iter().flat_map(|g| g.triangles))
+            .map(ObjTriangle::into_shape)
+            .collect()
+    }
+
+    /// The whole model as one group, with a child group per named group in the file, so it
+    /// can be placed in a world with a single transform.
+    pub fn into_group(self) -> Group {
+        let mut model = Group::new();
+        for triangle in self.default_group {
+            model.add_child(triangle.into_shape());
+        }
+        for named in self.named_groups {
+            let mut group = Group::new();
+            for triangle in named.triangles {
+                group.add_child(triangle.into_shape());
+            }
+            model.add_child(Box::new(group));
+        }
+        model
+    }
+
+    fn current_group(&mut self, name: &Option<String>) -> &mut Vec<ObjTriangle> {
+        match name {
+            None => &mut self.default_group,
+            Some(name) => {
+                let idx = match self.named_groups.iter().position(|g| &g.name == name) {
+                    Some(idx) => idx,
+                    None => {
+                        self.named_groups.push(ObjGroup {
+                            name: name.clone(),
+                            triangles: vec![],
+                        });
+                        self.named_groups.len() - 1
+                    }
+                };
+                &mut self.named_groups[idx].triangles
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(std::io::Error),
+    Malformed { line: usize, message: String },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io(e) => write!(f, "could not read OBJ file: {}", e),
+            ObjError::Malformed { line, message } => {
+                write!(f, "malformed OBJ file at line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+impl From<std::io::Error> for ObjError {
+    fn from(e: std::io::Error) -> Self {
+        ObjError::Io(e)
+    }
+}
+
+pub fn read_obj_file(path: &str) -> Result<ObjFile, ObjError> {
+    parse_obj(&fs::read_to_string(path)?)
+}
+
+pub fn parse_obj(input: &str) -> Result<ObjFile, ObjError> {
+    let mut obj = ObjFile::new();
+    let mut group: Option<String> = None;
+
+    for (idx, line) in input.lines().enumerate() {
+        let line_number = idx + 1;
+        let mut tokens = line.split_whitespace();
+        let arguments: Vec<&str> = tokens.clone().skip(1).collect();
+
+        match tokens.next() {
+            None => {}
+            Some("v") => {
+                let [x, y, z] = parse_floats::<3>(&arguments, line_number)?;
+                obj.vertices.push(Tuple::point(x, y, z));
+            }
+            Some("vn") => {
+                let [x, y, z] = parse_floats::<3>(&arguments, line_number)?;
+                obj.normals.push(Tuple::vector(x, y, z));
+            }
+            Some("vt") => {
+                // Only u is required; v defaults to 0.
+                let [u] = parse_floats::<1>(&arguments, line_number)?;
+                let v = match arguments.len() {
+                    1 => 0.0,
+                    _ => parse_floats::<2>(&arguments, line_number)?[1],
+                };
+                obj.texture_coordinates.push((u, v));
+            }
+            Some("f") => {
+                let triangles = parse_face(&obj, &arguments, line_number)?;
+                obj.current_group(&group).extend(triangles);
+            }
+            // A bare `g` goes back to the default group.
+            Some("g") if arguments.is_empty() => group = None,
+            Some("g") => group = Some(arguments.join(" ")),
+            Some(_) => obj.ignored_lines.push(line_number),
+        }
+    }
+
+    Ok(obj)
+}
+
+fn malformed(line: usize, message: &str) -> ObjError {
+    ObjError::Malformed {
+        line,
+        message: message.to_owned(),
+    }
+}
+
+/// Parses the first `N` arguments as floats. Any further (optional) arguments, like the
+/// w-component of a vertex, are not used by the renderer and are skipped.
+fn parse_floats<const N: usize>(arguments: &[&str], line: usize) -> Result<[f64; N], ObjError> {
+    if arguments.len() < N {
+        return Err(malformed(
+            line,
+            &format!("expected {} numbers, found {}", N, arguments.len()),
+        ));
+    }
+
+    let mut values = [0.0; N];
+    for (value, argument) in values.iter_mut().zip(arguments) {
+        *value = argument
+            .parse()
+            .map_err(|_| malformed(line, &format!("'{}' is not a number", argument)))?;
+    }
+
+    Ok(values)
+}
+
+/// Resolves a 1-based (or negative, counting back from the end) OBJ index into `count` items.
+fn resolve_index(raw: &str, count: usize, line: usize) -> Result<usize, ObjError> {
+    let index: i64 = raw
+        .parse()
+        .map_err(|_| malformed(line, &format!("'{}' is not an index", raw)))?;
+
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(malformed(
+            line,
+            &format!("index {} out of range, only {} defined", index, count),
+        ));
+    }
+
+    Ok(resolved as usize)
+}
+
+/// Parses the vertices of a face, given as `v`, `v/vt`, `v//vn` or `v/vt/vn`, and
+/// fan-triangulates polygons with more than three vertices around the first one.
+fn parse_face(
+    obj: &ObjFile,
+    arguments: &[&str],
+    line: usize,
+) -> Result<Vec<ObjTriangle>, ObjError> {
+    if arguments.len() < 3 {
+        return Err(malformed(line, "a face needs at least three vertices"));
+    }
+
+    let mut vertices = vec![];
+    let mut normals = vec![];
+    for argument in arguments {
+        let mut parts = argument.split('/');
+
+        let vertex = parts.next().unwrap_or_default();
+        vertices.push(obj.vertices[resolve_index(vertex, obj.vertices.len(), line)?]);
+
+        if let Some(texture) = parts.next().filter(|t| !t.is_empty()) {
+            resolve_index(texture, obj.texture_coordinates.len(), line)?;
+        }
+
+        if let Some(normal) = parts.next().filter(|n| !n.is_empty()) {
+            normals.push(obj.normals[resolve_index(normal, obj.normals.len(), line)?]);
+        }
+    }
+
+    // Only interpolate normals if every vertex of the face has one.
+    let smooth = normals.len() == vertices.len();
+
+    let mut triangles = vec![];
+    for i in 1..vertices.len() - 1 {
+        let triangle = if smooth {
+            ObjTriangle::Smooth(SmoothTriangle::new(
+                vertices[0],
+                vertices[i],
+                vertices[i + 1],
+                normals[0],
+                normals[i],
+                normals[i + 1],
+            ))
+        } else {
+            ObjTriangle::Flat(Triangle::new(vertices[0], vertices[i], vertices[i + 1]))
+        };
+        triangles.push(triangle);
+    }
+
+    Ok(triangles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ray::Ray;
+
+    fn flat(triangle: &ObjTriangle) -> &Triangle {
+        match triangle {
+            ObjTriangle::Flat(t) => t,
+            ObjTriangle::Smooth(_) => panic!("expected a flat triangle"),
+        }
+    }
+
+    fn smooth(triangle: &ObjTriangle) -> &SmoothTriangle {
+        match triangle {
+            ObjTriangle::Smooth(t) => t,
+            ObjTriangle::Flat(_) => panic!("expected a smooth triangle"),
+        }
+    }
+
+    #[test]
+    fn ignore_unrecognized_lines() {
+        let gibberish = "There was a young lady named Bright
+who traveled much faster than light.
+She set out one day
+
+in a relative way,
+and came back the previous night.";
+
+        let obj = parse_obj(gibberish).unwrap();
+
+        assert_eq!(obj.ignored_lines, vec![1, 2, 3, 5, 6]);
+    }
+
+    #[test]
+    fn parse_vertex_records() {
+        let file = "v -1 1 0
+v -1.0000 0.5000 0.0000
+v 1 0 0
+v 1 1 0";
+
+        let obj = parse_obj(file).unwrap();
+
+        assert_eq!(obj.vertices[0], Tuple::point(-1.0, 1.0, 0.0));
+        assert_eq!(obj.vertices[1], Tuple::point(-1.0, 0.5, 0.0));
+        assert_eq!(obj.vertices[2], Tuple::point(1.0, 0.0, 0.0));
+        assert_eq!(obj.vertices[3], Tuple::point(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn parse_triangle_faces() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+f 1 3 4";
+
+        let obj = parse_obj(file).unwrap();
+
+        let t1 = flat(&obj.default_group[0]);
+        let t2 = flat(&obj.default_group[1]);
+        assert_eq!(t1.p1, obj.vertices[0]);
+        assert_eq!(t1.p2, obj.vertices[1]);
+        assert_eq!(t1.p3, obj.vertices[2]);
+        assert_eq!(t2.p1, obj.vertices[0]);
+        assert_eq!(t2.p2, obj.vertices[2]);
+        assert_eq!(t2.p3, obj.vertices[3]);
+    }
+
+    #[test]
+    fn triangulate_polygons() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+v 0 2 0
+
+f 1 2 3 4 5";
+
+        let obj = parse_obj(file).unwrap();
+
+        assert_eq!(obj.default_group.len(), 3);
+        let t3 = flat(&obj.default_group[2]);
+        assert_eq!(t3.p1, obj.vertices[0]);
+        assert_eq!(t3.p2, obj.vertices[3]);
+        assert_eq!(t3.p3, obj.vertices[4]);
+    }
+
+    #[test]
+    fn triangles_in_named_groups() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+
+        let obj = parse_obj(file).unwrap();
+
+        assert!(obj.default_group.is_empty());
+        let g1 = obj.group("FirstGroup").unwrap();
+        let g2 = obj.group("SecondGroup").unwrap();
+        assert_eq!(flat(&g1.triangles[0]).p2, obj.vertices[1]);
+        assert_eq!(flat(&g2.triangles[0]).p3, obj.vertices[3]);
+    }
+
+    #[test]
+    fn bare_group_statement_returns_to_default_group() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+g FirstGroup
+f 1 2 3
+g
+f 1 3 4";
+
+        let obj = parse_obj(file).unwrap();
+
+        assert_eq!(obj.group("FirstGroup").unwrap().triangles.len(), 1);
+        assert_eq!(obj.default_group.len(), 1);
+        assert_eq!(flat(&obj.default_group[0]).p3, obj.vertices[3]);
+    }
+
+    #[test]
+    fn convert_obj_file_to_group() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+f 1 2 4
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+
+        let model = parse_obj(file).unwrap().into_group();
+
+        // The loose triangle, then one child group per named group.
+        assert_eq!(model.len(), 3);
+        let r = Ray::new(Tuple::point(0.5, 0.9, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(r.intersect(&model).len(), 2);
+    }
+
+    #[test]
+    fn parse_vertex_normals_and_texture_coordinates() {
+        let file = "vn 0 0 1
+vn 0.707 0 -0.707
+vn 1 2 3
+vt 0.5 0.25";
+
+        let obj = parse_obj(file).unwrap();
+
+        assert_eq!(obj.normals[0], Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(obj.normals[1], Tuple::vector(0.707, 0.0, -0.707));
+        assert_eq!(obj.normals[2], Tuple::vector(1.0, 2.0, 3.0));
+        assert_eq!(obj.texture_coordinates[0], (0.5, 0.25));
+    }
+
+    #[test]
+    fn texture_coordinates_take_one_to_three_numbers() {
+        let file = "vt 0.5
+vt 0.5 0.25
+vt 0.5 0.25 1";
+
+        let obj = parse_obj(file).unwrap();
+
+        assert_eq!(obj.texture_coordinates[0], (0.5, 0.0));
+        assert_eq!(obj.texture_coordinates[1], (0.5, 0.25));
+        assert_eq!(obj.texture_coordinates[2], (0.5, 0.25));
+        assert!(obj.ignored_lines.is_empty());
+    }
+
+    #[test]
+    fn texture_coordinates_without_numbers_report_line() {
+        match parse_obj("vt 0 0\nvt") {
+            Err(ObjError::Malformed { line, .. }) => assert_eq!(line, 2),
+            other => panic!("expected a malformed error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn faces_with_normals_become_smooth_triangles() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+
+vt 0 0
+
+f 1//3 2//1 3//2
+f 1/1/3 2/1/1 3/1/2";
+
+        let obj = parse_obj(file).unwrap();
+
+        for triangle in &obj.default_group {
+            let t = smooth(triangle);
+            assert_eq!(t.p1, obj.vertices[0]);
+            assert_eq!(t.p2, obj.vertices[1]);
+            assert_eq!(t.p3, obj.vertices[2]);
+            assert_eq!(t.n1, obj.normals[2]);
+            assert_eq!(t.n2, obj.normals[0]);
+            assert_eq!(t.n3, obj.normals[1]);
+        }
+    }
+
+    #[test]
+    fn faces_with_only_texture_coordinates_stay_flat() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vt 0 0
+f 1/1 2/1 3/1";
+
+        let obj = parse_obj(file).unwrap();
+
+        assert_eq!(flat(&obj.default_group[0]).p1, obj.vertices[0]);
+    }
+
+    #[test]
+    fn negative_indices_count_from_the_end() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+f -3 -2 -1";
+
+        let obj = parse_obj(file).unwrap();
+
+        let t = flat(&obj.default_group[0]);
+        assert_eq!(t.p1, obj.vertices[0]);
+        assert_eq!(t.p3, obj.vertices[2]);
+    }
+
+    #[test]
+    fn malformed_vertex_reports_line() {
+        let file = "v 0 1 0
+v -1 zero 0";
+
+        match parse_obj(file) {
+            Err(ObjError::Malformed { line, .. }) => assert_eq!(line, 2),
+            other => panic!("expected a malformed error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn face_with_unknown_vertex_reports_line() {
+        let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+
+f 1 2 4";
+
+        match parse_obj(file) {
+            Err(ObjError::Malformed { line, .. }) => assert_eq!(line, 5),
+            other => panic!("expected a malformed error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn face_with_too_few_vertices_reports_line() {
+        let file = "v 0 1 0
+v -1 0 0
+f 1 2";
+
+        match parse_obj(file) {
+            Err(ObjError::Malformed { line, .. }) => assert_eq!(line, 3),
+            other => panic!("expected a malformed error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn into_shapes_collects_every_group() {
+        let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+f 1 2 3
+g Second
+f 1 3 4";
+
+        let shapes = parse_obj(file).unwrap().into_shapes();
+
+        assert_eq!(shapes.len(), 2);
+    }
+}
@@ -0,0 +1,584 @@
+use crate::color::Color;
+use crate::matrix::Matrix;
+use crate::noise::Perlin;
+use crate::shape::Shape;
+use crate::tuple::Tuple;
+use std::rc::Rc;
+
+/// A color that varies over the surface of a shape.
+///
+/// Like shapes, patterns only describe themselves in their own space through
+/// `local_pattern_at`. A pattern's transform places it within the object space of the shape
+/// it is applied to, so moving the shape moves the pattern along with it. Patterns may be
+/// built from other patterns, in which case the inner pattern's transform places it within
+/// the space of the outer one.
+pub trait Pattern: std::fmt::Debug {
+    fn local_pattern_at(&self, point: &Tuple) -> Color;
+
+    fn get_transform(&self) -> &Matrix;
+    fn get_inverse_transform(&self) -> &Matrix;
+    fn set_transform(&mut self, transform: &Matrix);
+
+    /// The color of the pattern at `point`, given in the space the pattern is placed in.
+    fn pattern_at(&self, point: &Tuple) -> Color {
+        self.local_pattern_at(&(self.get_inverse_transform() * *point))
+    }
+
+    /// The color of the pattern at `world_point` on `object`.
+    fn pattern_at_shape(&self, object: &dyn Shape, world_point: &Tuple) -> Color {
+        self.pattern_at(&object.world_to_object(world_point))
+    }
+}
+
+/// A single color everywhere, mostly useful as a building block of other patterns.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Solid {
+    pub color: Color,
+    transform: Matrix,
+    inverse_transform: Matrix,
+}
+
+impl Solid {
+    pub fn new(color: Color) -> Self {
+        Solid {
+            color,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+        }
+    }
+}
+
+impl Pattern for Solid {
+    fn local_pattern_at(&self, _point: &Tuple) -> Color {
+        self.color
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+}
+
+/// Alternates between `a` and `b` every unit along the x-axis.
+#[derive(Debug, Clone)]
+pub struct Stripe {
+    pub a: Rc<dyn Pattern>,
+    pub b: Rc<dyn Pattern>,
+    transform: Matrix,
+    inverse_transform: Matrix,
+}
+
+impl Stripe {
+    pub fn new(a: Color, b: Color) -> Self {
+        Stripe::nested(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+    }
+
+    /// Like `new`, but with patterns in place of the two colors.
+    pub fn nested(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+        Stripe {
+            a,
+            b,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+        }
+    }
+}
+
+impl Pattern for Stripe {
+    fn local_pattern_at(&self, point: &Tuple) -> Color {
+        if point.x.floor().rem_euclid(2.0) == 0.0 {
+            self.a.pattern_at(point)
+        } else {
+            self.b.pattern_at(point)
+        }
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+}
+
+/// Blends linearly from `a` to `b` between x = 0 and x = 1, repeating every unit.
+#[derive(Debug, Clone)]
+pub struct Gradient {
+    pub a: Rc<dyn Pattern>,
+    pub b: Rc<dyn Pattern>,
+    transform: Matrix,
+    inverse_transform: Matrix,
+}
+
+impl Gradient {
+    pub fn new(a: Color, b: Color) -> Self {
+        Gradient::nested(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+    }
+
+    /// Like `new`, but with patterns in place of the two colors.
+    pub fn nested(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+        Gradient {
+            a,
+            b,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+        }
+    }
+}
+
+impl Pattern for Gradient {
+    fn local_pattern_at(&self, point: &Tuple) -> Color {
+        let fraction = point.x - point.x.floor();
+        let a = self.a.pattern_at(point);
+        a + (self.b.pattern_at(point) - a) * fraction
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+}
+
+/// Concentric rings around the y-axis, alternating between `a` and `b` every unit of radius.
+#[derive(Debug, Clone)]
+pub struct Ring {
+    pub a: Rc<dyn Pattern>,
+    pub b: Rc<dyn Pattern>,
+    transform: Matrix,
+    inverse_transform: Matrix,
+}
+
+impl Ring {
+    pub fn new(a: Color, b: Color) -> Self {
+        Ring::nested(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+    }
+
+    /// Like `new`, but with patterns in place of the two colors.
+    pub fn nested(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+        Ring {
+            a,
+            b,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+        }
+    }
+}
+
+impl Pattern for Ring {
+    fn local_pattern_at(&self, point: &Tuple) -> Color {
+        let distance = (point.x * point.x + point.z * point.z).sqrt();
+        if distance.floor().rem_euclid(2.0) == 0.0 {
+            self.a.pattern_at(point)
+        } else {
+            self.b.pattern_at(point)
+        }
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+}
+
+/// Unit cubes alternating between `a` and `b` in all three dimensions.
+#[derive(Debug, Clone)]
+pub struct Checker {
+    pub a: Rc<dyn Pattern>,
+    pub b: Rc<dyn Pattern>,
+    transform: Matrix,
+    inverse_transform: Matrix,
+}
+
+impl Checker {
+    pub fn new(a: Color, b: Color) -> Self {
+        Checker::nested(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+    }
+
+    /// Like `new`, but with patterns in place of the two colors.
+    pub fn nested(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+        Checker {
+            a,
+            b,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+        }
+    }
+}
+
+impl Pattern for Checker {
+    fn local_pattern_at(&self, point: &Tuple) -> Color {
+        let sum = point.x.floor() + point.y.floor() + point.z.floor();
+        if sum.rem_euclid(2.0) == 0.0 {
+            self.a.pattern_at(point)
+        } else {
+            self.b.pattern_at(point)
+        }
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+}
+
+/// The average of two patterns.
+#[derive(Debug, Clone)]
+pub struct Blended {
+    pub a: Rc<dyn Pattern>,
+    pub b: Rc<dyn Pattern>,
+    transform: Matrix,
+    inverse_transform: Matrix,
+}
+
+impl Blended {
+    pub fn new(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+        Blended {
+            a,
+            b,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+        }
+    }
+}
+
+impl Pattern for Blended {
+    fn local_pattern_at(&self, point: &Tuple) -> Color {
+        (self.a.pattern_at(point) + self.b.pattern_at(point)) * 0.5
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+}
+
+/// Another pattern, evaluated at a point jittered by Perlin noise. Perturbing stripes or
+/// rings by a fraction of their width gives marble and wood grain looks.
+#[derive(Debug, Clone)]
+pub struct Perturbed {
+    pub pattern: Rc<dyn Pattern>,
+    /// How far, at most, the point is moved along each axis.
+    pub scale: f64,
+    noise: Perlin,
+    transform: Matrix,
+    inverse_transform: Matrix,
+}
+
+impl Perturbed {
+    pub fn new(pattern: Rc<dyn Pattern>, scale: f64, seed: u64) -> Self {
+        Perturbed {
+            pattern,
+            scale,
+            noise: Perlin::new(seed),
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+        }
+    }
+}
+
+impl Pattern for Perturbed {
+    fn local_pattern_at(&self, point: &Tuple) -> Color {
+        // Offset the noise per axis, so the point is not just moved along the diagonal.
+        let (x, y, z) = (point.x, point.y, point.z);
+        let jittered = Tuple::point(
+            x + self.noise.noise(x, y, z) * self.scale,
+            y + self.noise.noise(x, y, z + 1.0) * self.scale,
+            z + self.noise.noise(x, y, z + 2.0) * self.scale,
+        );
+
+        self.pattern.pattern_at(&jittered)
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sphere::Sphere;
+    use crate::transformations::{rotation_y, scaling, translation};
+    use std::f64::consts::PI;
+
+    fn white() -> Color {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    fn black() -> Color {
+        Color::black()
+    }
+
+    /// A pattern that shows where it was evaluated, by returning the point as a color.
+    #[derive(Debug)]
+    struct TestPattern {
+        transform: Matrix,
+        inverse_transform: Matrix,
+    }
+
+    impl TestPattern {
+        fn new() -> Self {
+            Self {
+                transform: Matrix::identity(4),
+                inverse_transform: Matrix::identity(4),
+            }
+        }
+    }
+
+    impl Pattern for TestPattern {
+        fn local_pattern_at(&self, point: &Tuple) -> Color {
+            Color::new(point.x, point.y, point.z)
+        }
+
+        fn get_transform(&self) -> &Matrix {
+            &self.transform
+        }
+
+        fn get_inverse_transform(&self) -> &Matrix {
+            &self.inverse_transform
+        }
+
+        fn set_transform(&mut self, transform: &Matrix) {
+            self.transform = transform.clone();
+            self.inverse_transform = transform.inverse();
+        }
+    }
+
+    #[test]
+    fn default_pattern_transform() {
+        let p = TestPattern::new();
+
+        assert_eq!(p.get_transform(), &Matrix::identity(4));
+    }
+
+    #[test]
+    fn pattern_with_object_transform() {
+        let mut s = Sphere::unit();
+        s.set_transform(&scaling(2.0, 2.0, 2.0));
+        let p = TestPattern::new();
+
+        let c = p.pattern_at_shape(&s, &Tuple::point(2.0, 3.0, 4.0));
+
+        assert_eq!(c, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_with_pattern_transform() {
+        let s = Sphere::unit();
+        let mut p = TestPattern::new();
+        p.set_transform(&scaling(2.0, 2.0, 2.0));
+
+        let c = p.pattern_at_shape(&s, &Tuple::point(2.0, 3.0, 4.0));
+
+        assert_eq!(c, Color::new(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn pattern_with_object_and_pattern_transform() {
+        let mut s = Sphere::unit();
+        s.set_transform(&scaling(2.0, 2.0, 2.0));
+        let mut p = TestPattern::new();
+        p.set_transform(&translation(0.5, 1.0, 1.5));
+
+        let c = p.pattern_at_shape(&s, &Tuple::point(2.5, 3.0, 3.5));
+
+        assert_eq!(c, Color::new(0.75, 0.5, 0.25));
+    }
+
+    #[test]
+    fn stripe_is_constant_in_y_and_z() {
+        let p = Stripe::new(white(), black());
+
+        for (y, z) in [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (0.0, 1.0), (0.0, 2.0)] {
+            assert_eq!(p.local_pattern_at(&Tuple::point(0.0, y, z)), white());
+        }
+    }
+
+    #[test]
+    fn stripe_alternates_in_x() {
+        let p = Stripe::new(white(), black());
+        let cases = [
+            (0.0, white()),
+            (0.9, white()),
+            (1.0, black()),
+            (-0.1, black()),
+            (-1.0, black()),
+            (-1.1, white()),
+        ];
+
+        for (x, color) in cases {
+            assert_eq!(p.local_pattern_at(&Tuple::point(x, 0.0, 0.0)), color);
+        }
+    }
+
+    #[test]
+    fn gradient_interpolates_between_colors() {
+        let p = Gradient::new(white(), black());
+        let cases = [
+            (0.0, white()),
+            (0.25, Color::new(0.75, 0.75, 0.75)),
+            (0.5, Color::new(0.5, 0.5, 0.5)),
+            (0.75, Color::new(0.25, 0.25, 0.25)),
+        ];
+
+        for (x, color) in cases {
+            assert_eq!(p.local_pattern_at(&Tuple::point(x, 0.0, 0.0)), color);
+        }
+    }
+
+    #[test]
+    fn ring_extends_in_x_and_z() {
+        let p = Ring::new(white(), black());
+
+        assert_eq!(p.local_pattern_at(&Tuple::origin()), white());
+        assert_eq!(p.local_pattern_at(&Tuple::point(1.0, 0.0, 0.0)), black());
+        assert_eq!(p.local_pattern_at(&Tuple::point(0.0, 0.0, 1.0)), black());
+        // 0.708 is just beyond sqrt(2) / 2.
+        assert_eq!(
+            p.local_pattern_at(&Tuple::point(0.708, 0.0, 0.708)),
+            black()
+        );
+    }
+
+    #[test]
+    fn checker_repeats_in_all_dimensions() {
+        let p = Checker::new(white(), black());
+
+        for (point, color) in [
+            (Tuple::origin(), white()),
+            (Tuple::point(0.99, 0.0, 0.0), white()),
+            (Tuple::point(1.01, 0.0, 0.0), black()),
+            (Tuple::point(0.0, 0.99, 0.0), white()),
+            (Tuple::point(0.0, 1.01, 0.0), black()),
+            (Tuple::point(0.0, 0.0, 0.99), white()),
+            (Tuple::point(0.0, 0.0, 1.01), black()),
+        ] {
+            assert_eq!(p.local_pattern_at(&point), color);
+        }
+    }
+
+    fn stripes(a: Color, b: Color, transform: &Matrix) -> Rc<dyn Pattern> {
+        let mut stripe = Stripe::new(a, b);
+        stripe.set_transform(transform);
+        Rc::new(stripe)
+    }
+
+    #[test]
+    fn nested_pattern_uses_sub_patterns_as_colors() {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let green = Color::new(0.0, 1.0, 0.0);
+        let p = Checker::nested(
+            stripes(white(), black(), &Matrix::identity(4)),
+            stripes(red, green, &scaling(0.25, 1.0, 1.0)),
+        );
+
+        // Both points are in the first square, but in different stripes.
+        assert_eq!(p.local_pattern_at(&Tuple::point(0.25, 0.0, 0.0)), white());
+        assert_eq!(p.local_pattern_at(&Tuple::point(0.75, 0.0, 0.0)), white());
+        // The second square has stripes a quarter unit wide.
+        assert_eq!(p.local_pattern_at(&Tuple::point(1.1, 0.0, 0.0)), red);
+        assert_eq!(p.local_pattern_at(&Tuple::point(1.3, 0.0, 0.0)), green);
+    }
+
+    #[test]
+    fn blended_pattern_averages_sub_patterns() {
+        let along_x = stripes(white(), black(), &Matrix::identity(4));
+        let along_z = stripes(white(), black(), &rotation_y(PI / 2.0));
+        let p = Blended::new(along_x, along_z);
+
+        // Turned a quarter, the second stripes are white wherever z is in [-1, 0).
+        assert_eq!(p.local_pattern_at(&Tuple::point(0.5, 0.0, -0.5)), white());
+        assert_eq!(
+            p.local_pattern_at(&Tuple::point(1.5, 0.0, -0.5)),
+            Color::new(0.5, 0.5, 0.5)
+        );
+        assert_eq!(p.local_pattern_at(&Tuple::point(1.5, 0.0, 0.5)), black());
+    }
+
+    #[test]
+    fn perturbed_pattern_without_scale_is_unchanged() {
+        let p = Perturbed::new(stripes(white(), black(), &Matrix::identity(4)), 0.0, 1);
+
+        assert_eq!(p.local_pattern_at(&Tuple::point(0.9, 0.3, 0.2)), white());
+        assert_eq!(p.local_pattern_at(&Tuple::point(1.1, 0.3, 0.2)), black());
+    }
+
+    #[test]
+    fn perturbed_pattern_moves_stripe_borders() {
+        let p = Perturbed::new(stripes(white(), black(), &Matrix::identity(4)), 0.5, 1);
+        let plain = Stripe::new(white(), black());
+
+        let differs = (0..100)
+            .map(|i| Tuple::point(0.9 + i as f64 * 0.002, i as f64 * 0.13, 0.37))
+            .any(|point| p.local_pattern_at(&point) != plain.local_pattern_at(&point));
+
+        assert!(differs);
+    }
+
+    #[test]
+    fn perturbed_pattern_is_reproducible() {
+        let stripe = stripes(white(), black(), &Matrix::identity(4));
+        let p1 = Perturbed::new(stripe.clone(), 0.5, 9);
+        let p2 = Perturbed::new(stripe, 0.5, 9);
+
+        for i in 0..50 {
+            let point = Tuple::point(i as f64 * 0.05, 0.7, -0.2);
+            assert_eq!(p1.local_pattern_at(&point), p2.local_pattern_at(&point));
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::tuple::Tuple;
+use crate::utils;
+
+/// An infinite plane, spanning the xz-plane in object space.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Plane {
+    transform: Matrix,
+    inverse_transform: Matrix,
+    parent_inverse_transform: Matrix,
+    material: Material,
+}
+
+impl Plane {
+    pub fn new() -> Self {
+        Plane {
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            parent_inverse_transform: Matrix::identity(4),
+            material: Material::default(),
+        }
+    }
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shape for Plane {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        // A ray parallel to the plane (or inside it) never crosses it.
+        if ray.direction.y.abs() <= utils::PARALLEL_EPSILON * ray.direction.abs() {
+            return Intersections { objects: vec![] };
+        }
+
+        let time = -ray.origin.y / ray.direction.y;
+        Intersections {
+            objects: vec![Intersection::new(time, self)],
+        }
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        Tuple::vector(0.0, 1.0, 0.0)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Tuple::point(f64::INFINITY, 0.0, f64::INFINITY),
+        )
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+
+    fn get_parent_inverse_transform(&self) -> &Matrix {
+        &self.parent_inverse_transform
+    }
+
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+        self.parent_inverse_transform = parent_inverse.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: &Material) {
+        self.material = material.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::shape::same_shape;
+    use crate::transformations::{rotation_x, scaling, translation};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_plane_default_transform() {
+        let p = Plane::new();
+        assert_eq!(p.transform, Matrix::identity(4));
+    }
+
+    #[test]
+    fn test_plane_set_transform() {
+        let mut p = Plane::new();
+        let t = scaling(1.0, 2.0, 3.0);
+
+        p.set_transform(&t);
+
+        assert_eq!(p.transform, t);
+    }
+
+    #[test]
+    fn plane_has_default_material() {
+        let p = Plane::new();
+        assert_eq!(p.material, Material::default());
+    }
+
+    #[test]
+    fn plane_can_be_assigned_material() {
+        let mut p = Plane::new();
+        let m = Material::new(Color::new(1.0, 0.0, 1.0), 1.0, 2.0, 3.0, 4.0);
+        p.set_material(&m);
+        assert_eq!(p.material, m);
+    }
+
+    #[test]
+    fn normal_of_plane_is_constant() {
+        let p = Plane::new();
+
+        assert_eq!(
+            p.local_normal_at(&Tuple::origin()),
+            Tuple::vector(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            p.local_normal_at(&Tuple::point(10.0, 0.0, -10.0)),
+            Tuple::vector(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            p.local_normal_at(&Tuple::point(-5.0, 0.0, 150.0)),
+            Tuple::vector(0.0, 1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn normal_of_transformed_plane() {
+        let mut p = Plane::new();
+        p.set_transform(&rotation_x(PI / 2.0));
+
+        assert_eq!(
+            p.normal_at(&Tuple::point(1.0, 0.0, 1.0)),
+            Tuple::vector(0.0, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn intersect_with_parallel_ray() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple::point(0.0, 10.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        assert_eq!(p.local_intersect(&r).len(), 0);
+    }
+
+    #[test]
+    fn intersect_with_coplanar_ray() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple::origin(), Tuple::vector(0.0, 0.0, 1.0));
+
+        assert_eq!(p.local_intersect(&r).len(), 0);
+    }
+
+    #[test]
+    fn intersect_from_above() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+        let xs = p.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].time, 1.0);
+        assert!(same_shape(xs[0].object, &p));
+    }
+
+    #[test]
+    fn intersect_from_below() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple::point(0.0, -1.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        let xs = p.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].time, 1.0);
+        assert!(same_shape(xs[0].object, &p));
+    }
+
+    #[test]
+    fn intersect_translated_plane() {
+        let mut p = Plane::new();
+        p.set_transform(&translation(0.0, -2.0, 0.0));
+        let r = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+        let xs = r.intersect(&p);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].time, 3.0);
+    }
+
+    #[test]
+    fn intersect_hugely_scaled_plane() {
+        let mut p = Plane::new();
+        p.set_transform(&scaling(1.0e6, 1.0e6, 1.0e6));
+        let r = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+        let xs = r.intersect(&p);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].time, 1.0);
+    }
+}
@@ -1,21 +1,16 @@
 use crate::intersection::Intersections;
-use crate::material::Material;
 use crate::matrix::Matrix;
+use crate::shape::Shape;
 use crate::tuple::Tuple;
 
+#[derive(Debug, Clone)]
 pub struct Ray {
     pub origin: Tuple,
     pub direction: Tuple,
 }
 
-pub trait Intersect: std::fmt::Debug {
-    fn ray_intersections(&self, ray: &Ray) -> Intersections;
-    fn get_transform(&self) -> Option<Matrix>;
-    fn get_material(&self) -> Option<Material>;
-}
-
 impl Ray {
-    fn new(origin: Tuple, direction: Tuple) -> Self {
+    pub fn new(origin: Tuple, direction: Tuple) -> Self {
         if origin.is_vector() || direction.is_point() {
             panic!("origin must be point and direction must be vector");
         }
@@ -26,16 +21,10 @@ impl Ray {
         self.origin + self.direction * t
     }
 
-    pub(crate) fn intersect(&self, object: &impl Intersect) -> Intersections {
-        let transform = object.get_transform();
-
-        match transform {
-            None => object.ray_intersections(self),
-            Some(t) => {
-                let ray_transformed = self.transform(&t.inverse());
-                object.ray_intersections(&ray_transformed)
-            }
-        }
+    /// Intersects the ray with `object`, after moving the ray into the object's own space.
+    pub(crate) fn intersect<'a>(&self, object: &'a dyn Shape) -> Intersections<'a> {
+        let ray_transformed = self.transform(object.get_inverse_transform());
+        object.local_intersect(&ray_transformed)
     }
 
     fn transform(&self, transformation: &Matrix) -> Self {
@@ -49,6 +38,7 @@ impl Ray {
 #[cfg(test)]
 mod tests {
     use crate::ray::Ray;
+    use crate::shape::{same_shape, Shape};
     use crate::sphere::Sphere;
     use crate::transformations::{scaling, translation};
     use crate::tuple::Tuple;
@@ -135,8 +125,8 @@ mod tests {
         let xs = r.intersect(&s);
 
         assert_eq!(xs.len(), 2);
-        assert_eq!(xs[0].object, s);
-        assert_eq!(xs[1].object, s);
+        assert!(same_shape(xs[0].object, &s));
+        assert!(same_shape(xs[1].object, &s));
     }
 
     #[test]
@@ -170,8 +160,8 @@ mod tests {
         let xs = r.intersect(&s);
 
         assert_eq!(xs.len(), 2);
-        assert_eq!(xs[0].object, s);
-        assert_eq!(xs[1].object, s);
+        assert!(same_shape(xs[0].object, &s));
+        assert!(same_shape(xs[1].object, &s));
 
         assert_eq!(xs[0].time, 3.0);
         assert_eq!(xs[1].time, 7.0);
@@ -1,62 +1,72 @@
-use crate::canvas::Canvas;
+use crate::camera::{render, Camera};
 use crate::color::Color;
+use crate::cylinder::Cylinder;
+use crate::environment::Environment;
+use crate::group::Group;
 use crate::image::write_to_file;
-use crate::lights::{lighting, PointLight};
+use crate::lights::{AreaLight, DirectionalLight, PointLight, SpotLight};
 use crate::material::Material;
-use crate::normals::Normal;
-use crate::ray::{Intersect, Ray};
+use crate::pattern::Pattern;
+use crate::plane::Plane;
+use crate::sampling::{Filter, SamplePattern, Supersampling};
+use crate::shape::Shape;
 use crate::sphere::Sphere;
+use crate::texture::{read_image_texture, TextureMap, UvMapping};
+use crate::transformations::{
+    rotation_x, rotation_y, rotation_z, scaling, translation, view_transform,
+};
 use crate::tuple::Tuple;
+use crate::world::World;
+use std::f64::consts::PI;
+use std::rc::Rc;
 
-pub fn render_sphere() {
-    let ray_origin = Tuple::point(0.0, 0.0, -5.0);
-    let wall_z = 10.0;
+/// A camera at (0, 0, -5) looking at the origin, framing the same 7x7 wall at z = 10
+/// that the original hand-written render loops used.
+fn sphere_camera(canvas_size: usize) -> Camera {
+    let wall_distance: f64 = 15.0;
     let wall_size = 7.0;
+    let field_of_view = 2.0 * (wall_size / 2.0 / wall_distance).atan();
 
-    let canvas_size = 100;
-    let mut c = Canvas::new(canvas_size, canvas_size);
-
-    let pixel_size = wall_size / canvas_size as f64;
-    let half = wall_size / 2.0;
+    let mut camera = Camera::new(canvas_size, canvas_size, field_of_view);
+    camera.set_transform(&view_transform(
+        &Tuple::point(0.0, 0.0, -5.0),
+        &Tuple::origin(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+    ));
 
-    let color = Color::new(1.0, 0.0, 0.0);
-    let shape = Sphere::unit();
+    camera
+}
 
-    for y in 0..c.height {
-        let world_y = half - pixel_size * y as f64;
-        for x in 0..c.width {
-            let world_x = -half + pixel_size * x as f64;
+pub fn render_sphere() {
+    let camera = sphere_camera(100);
 
-            let position = Tuple::point(world_x, world_y, wall_z);
+    // A purely ambient material renders the sphere as a flat silhouette.
+    let mut shape = Sphere::unit();
+    let mut material = Material::default();
+    material.color = Color::new(1.0, 0.0, 0.0);
+    material.ambient = 1.0;
+    material.diffuse = 0.0;
+    material.specular = 0.0;
+    shape.set_material(&material);
 
-            let ray = Ray {
-                origin: ray_origin,
-                direction: (position - ray_origin).norm(),
-            };
+    let mut world = World::new();
+    world.add_object(Box::new(shape));
+    world.add_light(Box::new(PointLight::new(
+        Tuple::origin(),
+        Color::new(1.0, 1.0, 1.0),
+    )));
 
-            let ix = ray.intersect(&shape);
-            match ix.get_hit() {
-                None => {}
-                Some(_) => {
-                    c.write_pixel(x, y, &color);
-                }
-            }
-        }
-    }
-
-    write_to_file(&c, "sphere.ppm");
+    write_to_file(&render(&camera, &world), "sphere.ppm");
 }
 
 pub fn render_sphere_with_shading() {
-    let ray_origin = Tuple::point(0.0, 0.0, -5.0);
-    let wall_z = 10.0;
-    let wall_size = 7.0;
-
-    let canvas_size = 500;
-    let mut c = Canvas::new(canvas_size, canvas_size);
-
-    let pixel_size = wall_size / canvas_size as f64;
-    let half = wall_size / 2.0;
+    let mut camera = sphere_camera(500);
+    camera.supersampling = Some(Supersampling::new(
+        16,
+        SamplePattern::Sobol,
+        Filter::Mitchell,
+        0,
+    ));
 
     let mut shape = Sphere::unit();
     let mut material = Material::default();
@@ -67,39 +77,246 @@ pub fn render_sphere_with_shading() {
     let light_color = Color::new(1.0, 1.0, 1.0);
     let light = PointLight::new(light_position, light_color);
 
-    for y in 0..c.height {
-        let world_y = half - pixel_size * y as f64;
-        for x in 0..c.width {
-            let world_x = -half + pixel_size * x as f64;
-
-            let position = Tuple::point(world_x, world_y, wall_z);
-
-            let ray = Ray {
-                origin: ray_origin,
-                direction: (position - ray_origin).norm(),
-            };
-
-            let ix = ray.intersect(&shape);
-            match ix.get_hit() {
-                None => {}
-                Some(hit) => {
-                    let intersection_point = ray.position(hit.time);
-                    let normal_vector = hit.object.normal_at(&intersection_point);
-                    let eye_vector = -ray.direction;
-
-                    let color = lighting(
-                        &hit.object.get_material().unwrap(),
-                        &light,
-                        &intersection_point,
-                        &eye_vector,
-                        &normal_vector,
-                    );
-
-                    c.write_pixel(x, y, &color);
-                }
-            }
-        }
+    let mut world = World::new();
+    world.add_object(Box::new(shape));
+    world.add_light(Box::new(light));
+
+    write_to_file(
+        &render(&camera, &world),
+        "../examples/ppm/sphere_shaded.ppm",
+    );
+}
+
+/// The shaded sphere under a classic three-point setup: a bright key light to one side, a dim
+/// fill light opposite it to soften the shadows, and a rim light behind to pick out the edge.
+pub fn render_sphere_with_three_point_lighting() {
+    let camera = sphere_camera(500);
+
+    let mut shape = Sphere::unit();
+    let mut material = Material::default();
+    material.color = Color::new(1.0, 0.0, 0.0);
+    material.ambient = 0.05;
+    shape.set_material(&material);
+
+    let mut world = World::new();
+    world.add_object(Box::new(shape));
+    world.add_light(Box::new(PointLight::new(
+        Tuple::point(-10.0, 10.0, -10.0),
+        Color::new(0.9, 0.9, 0.9),
+    )));
+    world.add_light(Box::new(PointLight::new(
+        Tuple::point(10.0, 2.0, -10.0),
+        Color::new(0.3, 0.3, 0.35),
+    )));
+    world.add_light(Box::new(PointLight::new(
+        Tuple::point(0.0, 10.0, 10.0),
+        Color::new(0.6, 0.6, 0.6),
+    )));
+
+    write_to_file(
+        &render(&camera, &world),
+        "../examples/ppm/sphere_three_point.ppm",
+    );
+}
+
+/// A sphere resting on a floor under a square area light, so its shadow fades out through
+/// a penumbra instead of ending in a hard edge.
+pub fn render_soft_shadows() {
+    let mut camera = Camera::new(400, 200, PI / 3.0);
+    camera.set_transform(&view_transform(
+        &Tuple::point(0.0, 2.0, -5.0),
+        &Tuple::point(0.0, 0.5, 0.0),
+        &Tuple::vector(0.0, 1.0, 0.0),
+    ));
+
+    let mut sphere = Sphere::unit();
+    sphere.set_transform(&translation(0.0, 1.0, 0.0));
+    let mut material = Material::default();
+    material.color = Color::new(0.2, 0.4, 0.9);
+    sphere.set_material(&material);
+
+    let mut light = AreaLight::new(
+        Tuple::point(-3.0, 6.0, -3.0),
+        Tuple::vector(2.0, 0.0, 0.0),
+        8,
+        Tuple::vector(0.0, 0.0, 2.0),
+        8,
+        Color::new(1.0, 1.0, 1.0),
+    );
+    light.set_jitter(0);
+
+    let mut world = World::new();
+    world.add_object(Box::new(Plane::new()));
+    world.add_object(Box::new(sphere));
+    world.add_light(Box::new(light));
+
+    write_to_file(&render(&camera, &world), "../examples/ppm/soft_shadows.ppm");
+}
+
+/// A sphere on a dark stage, picked out by a spot light from above and in front.
+pub fn render_spot_light() {
+    let mut camera = Camera::new(400, 200, PI / 3.0);
+    camera.set_transform(&view_transform(
+        &Tuple::point(0.0, 2.0, -5.0),
+        &Tuple::point(0.0, 0.5, 0.0),
+        &Tuple::vector(0.0, 1.0, 0.0),
+    ));
+
+    let mut sphere = Sphere::unit();
+    sphere.set_transform(&translation(0.0, 1.0, 0.0));
+    let mut material = Material::default();
+    material.color = Color::new(0.9, 0.8, 0.2);
+    material.ambient = 0.02;
+    sphere.set_material(&material);
+
+    let mut floor = Plane::new();
+    material.color = Color::new(0.8, 0.8, 0.8);
+    floor.set_material(&material);
+
+    let mut world = World::new();
+    world.add_object(Box::new(floor));
+    world.add_object(Box::new(sphere));
+    world.add_light(Box::new(SpotLight::new(
+        Tuple::point(0.0, 6.0, -3.0),
+        Tuple::vector(0.0, -6.0, 3.0),
+        PI / 12.0,
+        PI / 8.0,
+        Color::new(1.0, 1.0, 1.0),
+    )));
+
+    write_to_file(&render(&camera, &world), "../examples/ppm/spot_light.ppm");
+}
+
+/// A row of spheres on a floor in late-afternoon sun, whose shadows all fall in parallel.
+pub fn render_sunlight() {
+    let mut camera = Camera::new(400, 200, PI / 3.0);
+    camera.set_transform(&view_transform(
+        &Tuple::point(0.0, 3.0, -8.0),
+        &Tuple::point(0.0, 0.5, 0.0),
+        &Tuple::vector(0.0, 1.0, 0.0),
+    ));
+
+    let mut world = World::new();
+    world.add_object(Box::new(Plane::new()));
+    for n in -2..=2 {
+        let mut sphere = Sphere::unit();
+        sphere.set_transform(
+            &(translation(n as f64 * 2.5, 0.5, n as f64 * 1.5) * scaling(0.5, 0.5, 0.5)),
+        );
+        world.add_object(Box::new(sphere));
+    }
+    world.add_light(Box::new(DirectionalLight::new(
+        Tuple::vector(1.0, -1.0, 1.0),
+        Color::new(1.0, 0.95, 0.85),
+    )));
+
+    write_to_file(&render(&camera, &world), "../examples/ppm/sunlight.ppm");
+}
+
+/// One side of the hexagon: a corner sphere and an edge cylinder, placed along the z-axis.
+fn hexagon_side() -> Group {
+    let mut corner = Sphere::unit();
+    corner.set_transform(&(translation(0.0, 0.0, -1.0) * scaling(0.25, 0.25, 0.25)));
+
+    let mut edge = Cylinder::truncated(0.0, 1.0, false);
+    edge.set_transform(
+        &(translation(0.0, 0.0, -1.0)
+            * rotation_y(-PI / 6.0)
+            * rotation_z(-PI / 2.0)
+            * scaling(0.25, 1.0, 0.25)),
+    );
+
+    let mut side = Group::new();
+    side.add_child(Box::new(corner));
+    side.add_child(Box::new(edge));
+    side
+}
+
+pub fn render_hexagon() {
+    let mut camera = Camera::new(500, 250, PI / 3.0);
+    camera.set_transform(&view_transform(
+        &Tuple::point(0.0, 2.0, -3.5),
+        &Tuple::origin(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+    ));
+
+    let mut hexagon = Group::new();
+    hexagon.set_transform(&rotation_x(-PI / 6.0));
+    for n in 0..6 {
+        let mut side = hexagon_side();
+        side.set_transform(&rotation_y(n as f64 * PI / 3.0));
+        hexagon.add_child(Box::new(side));
     }
+    let mut material = Material::default();
+    material.color = Color::new(0.8, 0.3, 0.1);
+    hexagon.set_material(&material);
+
+    let mut world = World::new();
+    world.add_object(Box::new(hexagon));
+    world.add_light(Box::new(PointLight::new(
+        Tuple::point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    )));
+
+    write_to_file(&render(&camera, &world), "../examples/ppm/hexagon.ppm");
+}
+
+/// Wraps the picture at `texture_path`, an equirectangular map such as a photo of the earth,
+/// around the unit sphere.
+pub fn render_earth(texture_path: &str) {
+    let camera = sphere_camera(500);
+
+    let texture = read_image_texture(texture_path).unwrap();
+    let mut earth_pattern = TextureMap::new(Rc::new(texture), UvMapping::Spherical);
+    // Maps start at the date line, so turn the prime meridian towards the camera.
+    earth_pattern.set_transform(&rotation_y(PI));
+
+    let mut earth = Sphere::unit();
+    let mut material = Material::default();
+    material.pattern = Some(Rc::new(earth_pattern));
+    material.specular = 0.1;
+    earth.set_material(&material);
+
+    let mut world = World::new();
+    world.add_object(Box::new(earth));
+    world.add_light(Box::new(PointLight::new(
+        Tuple::point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    )));
+
+    write_to_file(&render(&camera, &world), "../examples/ppm/earth.ppm");
+}
+
+/// A mirror sphere and a glass sphere set in the HDR panorama at `environment_path`, which
+/// shows behind them and in their reflections.
+pub fn render_studio(environment_path: &str) {
+    let mut camera = Camera::new(500, 250, PI / 3.0);
+    camera.set_transform(&view_transform(
+        &Tuple::point(0.0, 1.0, -5.0),
+        &Tuple::origin(),
+        &Tuple::vector(0.0, 1.0, 0.0),
+    ));
+
+    let mut mirror = Sphere::unit();
+    mirror.set_transform(&translation(-1.2, 0.0, 0.0));
+    let mut material = Material::default();
+    material.color = Color::black();
+    material.diffuse = 0.1;
+    material.reflective = 0.9;
+    mirror.set_material(&material);
+
+    let mut glass = Sphere::glass();
+    glass.set_transform(&translation(1.2, 0.0, 0.0));
+
+    let mut world = World::new();
+    world.add_object(Box::new(mirror));
+    world.add_object(Box::new(glass));
+    world.add_light(Box::new(PointLight::new(
+        Tuple::point(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    )));
+    let texture = read_image_texture(environment_path).unwrap();
+    world.environment = Some(Environment::new(Rc::new(texture)));
 
-    write_to_file(&c, "../examples/ppm/sphere_shaded.ppm");
+    write_to_file(&render(&camera, &world), "../examples/ppm/studio.ppm");
 }
@@ -0,0 +1,522 @@
+use crate::canvas::Canvas;
+use crate::color::Color;
+use crate::rng::Rng;
+
+/// Where within a pixel the rays are cast, as points in the unit square.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SamplePattern {
+    /// The centers of an evenly spaced grid. The sample count is rounded up to a square.
+    Regular,
+    /// One random point in each cell of the grid, which trades the aliasing of a regular grid
+    /// for noise. The sample count is rounded up to a square.
+    Jittered,
+    /// The Halton sequence in bases 2 and 3, which fills the square evenly for any count.
+    Halton,
+    /// The first two dimensions of the Sobol sequence, which are even better spread out when
+    /// the count is a power of two.
+    Sobol,
+}
+
+impl SamplePattern {
+    /// About `count` points in [0, 1)². The sequences are shifted by a random offset, wrapping
+    /// around, so that neighbouring pixels do not all share the same points.
+    pub fn points(&self, count: usize, rng: &mut Rng) -> Vec<(f64, f64)> {
+        match self {
+            SamplePattern::Regular => grid(count, || (0.5, 0.5)),
+            SamplePattern::Jittered => grid(count, || (rng.next_f64(), rng.next_f64())),
+            SamplePattern::Halton => shifted(count, rng, |i| {
+                (radical_inverse(2, i), radical_inverse(3, i))
+            }),
+            SamplePattern::Sobol => shifted(count, rng, |i| (sobol_x(i), sobol_y(i))),
+        }
+    }
+
+    /// How many points `points` returns when asked for `count`.
+    pub fn count(&self, count: usize) -> usize {
+        match self {
+            SamplePattern::Regular | SamplePattern::Jittered => grid_side(count).pow(2),
+            SamplePattern::Halton | SamplePattern::Sobol => count,
+        }
+    }
+}
+
+/// The side of the smallest square grid with at least `count` cells.
+fn grid_side(count: usize) -> usize {
+    (count as f64).sqrt().ceil().max(1.0) as usize
+}
+
+/// A point in each cell of the smallest square grid with at least `count` cells, placed
+/// within its cell at `offset`.
+fn grid(count: usize, mut offset: impl FnMut() -> (f64, f64)) -> Vec<(f64, f64)> {
+    let side = grid_side(count);
+
+    (0..side)
+        .flat_map(|v| (0..side).map(move |u| (u, v)))
+        .map(|(u, v)| {
+            let (du, dv) = offset();
+            ((u as f64 + du) / side as f64, (v as f64 + dv) / side as f64)
+        })
+        .collect()
+}
+
+fn shifted(count: usize, rng: &mut Rng, sequence: impl Fn(u32) -> (f64, f64)) -> Vec<(f64, f64)> {
+    let (shift_u, shift_v) = (rng.next_f64(), rng.next_f64());
+
+    (0..count as u32)
+        .map(|i| {
+            let (u, v) = sequence(i);
+            ((u + shift_u).fract(), (v + shift_v).fract())
+        })
+        .collect()
+}
+
+/// The digits of `index` in `base`, mirrored around the decimal point.
+fn radical_inverse(base: u32, mut index: u32) -> f64 {
+    let mut result = 0.0;
+    let mut digit_value = 1.0 / base as f64;
+    while index > 0 {
+        result += (index % base) as f64 * digit_value;
+        index /= base;
+        digit_value /= base as f64;
+    }
+    result
+}
+
+/// The first Sobol dimension, which is the base 2 radical inverse.
+fn sobol_x(index: u32) -> f64 {
+    index.reverse_bits() as f64 / (1u64 << 32) as f64
+}
+
+/// The second Sobol dimension, whose direction numbers come from the polynomial x + 1.
+fn sobol_y(mut index: u32) -> f64 {
+    let mut direction = 1u32 << 31;
+    let mut result = 0;
+    while index > 0 {
+        if index & 1 == 1 {
+            result ^= direction;
+        }
+        index >>= 1;
+        direction ^= direction >> 1;
+    }
+    result as f64 / (1u64 << 32) as f64
+}
+
+/// How much a sample counts towards a pixel, by its distance from the pixel center in pixels.
+/// Filters wider than a pixel blur a little but alias less.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Filter {
+    /// Every sample within the pixel counts the same.
+    Box,
+    /// Falls off linearly to nothing one pixel away.
+    Tent,
+    /// A bell curve, cut off at a pixel and a half.
+    Gaussian,
+    /// The Mitchell-Netravali cubic with B = C = 1/3. Its slightly negative lobes keep edges
+    /// crisper than the Gaussian.
+    Mitchell,
+}
+
+impl Filter {
+    /// How far from the pixel center, in pixels, samples are taken.
+    pub fn radius(&self) -> f64 {
+        match self {
+            Filter::Box => 0.5,
+            Filter::Tent => 1.0,
+            Filter::Gaussian => 1.5,
+            Filter::Mitchell => 2.0,
+        }
+    }
+
+    /// The weight of a sample at offset (`dx`, `dy`) from the pixel center.
+    pub fn weight(&self, dx: f64, dy: f64) -> f64 {
+        self.weight_1d(dx) * self.weight_1d(dy)
+    }
+
+    fn weight_1d(&self, x: f64) -> f64 {
+        let x = x.abs();
+        let radius = self.radius();
+        if x > radius {
+            return 0.0;
+        }
+
+        match self {
+            Filter::Box => 1.0,
+            Filter::Tent => 1.0 - x,
+            Filter::Gaussian => {
+                // Shifted down so the curve reaches zero at the radius instead of stopping short.
+                let gaussian = |x: f64| (-2.0 * x * x).exp();
+                gaussian(x) - gaussian(radius)
+            }
+            Filter::Mitchell => {
+                let (b, c) = (1.0 / 3.0, 1.0 / 3.0);
+                let polynomial = if x < 1.0 {
+                    (12.0 - 9.0 * b - 6.0 * c) * x.powi(3)
+                        + (-18.0 + 12.0 * b + 6.0 * c) * x.powi(2)
+                        + (6.0 - 2.0 * b)
+                } else {
+                    (-b - 6.0 * c) * x.powi(3)
+                        + (6.0 * b + 30.0 * c) * x.powi(2)
+                        + (-12.0 * b - 48.0 * c) * x
+                        + (8.0 * b + 24.0 * c)
+                };
+                polynomial / 6.0
+            }
+        }
+    }
+}
+
+/// Casts several rays per pixel and blends their colors with a reconstruction filter.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Supersampling {
+    /// How many rays to cast per pixel. The grid patterns round this up to a square, which
+    /// `samples_per_pixel` accounts for.
+    pub samples: usize,
+    pub pattern: SamplePattern,
+    pub filter: Filter,
+    /// Everything random about the samples is drawn from this, so a render can be repeated
+    /// exactly.
+    pub seed: u64,
+}
+
+impl Supersampling {
+    /// Panics if `samples` is 0, since a pixel without samples has no color.
+    pub fn new(samples: usize, pattern: SamplePattern, filter: Filter, seed: u64) -> Self {
+        assert!(
+            samples > 0,
+            "supersampling needs at least one sample per pixel"
+        );
+
+        Supersampling {
+            samples,
+            pattern,
+            filter,
+            seed,
+        }
+    }
+
+    /// How many rays are actually cast per pixel, once the pattern has rounded `samples`.
+    pub fn samples_per_pixel(&self) -> usize {
+        self.pattern.count(self.samples)
+    }
+
+    /// The samples for the pixel with index `pixel`, as offsets (dx, dy) from its center. The
+    /// samples stay inside the pixel; the filter spreads them to its neighbours on the `Film`.
+    pub fn pixel_samples(&self, pixel: usize) -> Vec<(f64, f64)> {
+        let mut rng = self.pixel_rng(pixel);
+
+        self.pattern
+            .points(self.samples, &mut rng)
+            .into_iter()
+            .map(|(u, v)| (u - 0.5, v - 0.5))
+            .collect()
+    }
+
+    /// A generator for one pixel, so that its samples depend only on the seed and on where
+    /// the pixel is, not on the order the pixels are rendered in.
+    fn pixel_rng(&self, pixel: usize) -> Rng {
+        let mut mixer = Rng::new(self.seed ^ (pixel as u64).rotate_left(32));
+        Rng::new(mixer.next_u64())
+    }
+}
+
+/// Collects the samples of a supersampled render. Each sample counts towards every pixel its
+/// filter reaches, and each pixel ends up as the weighted average of all the samples around it.
+#[derive(Debug)]
+pub struct Film {
+    pub width: usize,
+    pub height: usize,
+    filter: Filter,
+    pixels: Vec<FilmPixel>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FilmPixel {
+    total: Color,
+    weight: f64,
+    /// The color of the sample closest to the pixel center and its distance from it, for
+    /// pixels whose weights do not add up to anything positive.
+    nearest: Option<(f64, Color)>,
+}
+
+impl FilmPixel {
+    fn new() -> Self {
+        FilmPixel {
+            total: Color::black(),
+            weight: 0.0,
+            nearest: None,
+        }
+    }
+
+    fn add(&mut self, color: Color, weight: f64, distance: f64) {
+        self.total = self.total + color * weight;
+        self.weight += weight;
+        if self.nearest.is_none_or(|(nearest, _)| distance < nearest) {
+            self.nearest = Some((distance, color));
+        }
+    }
+
+    /// The weighted average of the samples. The negative lobes of a filter can cancel out the
+    /// rest, and dividing by what little weight is left would blow the color up, so such a
+    /// pixel takes the color of its nearest sample instead.
+    fn color(&self) -> Color {
+        if self.weight > 0.0 {
+            self.total * (1.0 / self.weight)
+        } else {
+            self.nearest.map_or(Color::black(), |(_, color)| color)
+        }
+    }
+}
+
+impl Film {
+    pub fn new(width: usize, height: usize, filter: Filter) -> Self {
+        Film {
+            width,
+            height,
+            filter,
+            pixels: vec![FilmPixel::new(); width * height],
+        }
+    }
+
+    /// Adds a sample of `color` taken at (`x`, `y`), measured in pixels from the top left
+    /// corner of the film, to every pixel whose center is within reach of the filter.
+    pub fn add_sample(&mut self, x: f64, y: f64, color: Color) {
+        let radius = self.filter.radius();
+        let first = |position: f64| (position - 0.5 - radius).ceil().max(0.0) as usize;
+        let last = |position: f64, size: usize| {
+            ((position - 0.5 + radius).floor().max(0.0) as usize).min(size.saturating_sub(1))
+        };
+
+        for py in first(y)..=last(y, self.height) {
+            for px in first(x)..=last(x, self.width) {
+                let (dx, dy) = (x - (px as f64 + 0.5), y - (py as f64 + 0.5));
+                let weight = self.filter.weight(dx, dy);
+                if weight != 0.0 {
+                    self.pixels[py * self.width + px].add(color, weight, dx.hypot(dy));
+                }
+            }
+        }
+    }
+
+    /// The finished image.
+    pub fn develop(&self) -> Canvas {
+        let mut image = Canvas::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                image.write_pixel(x, y, &self.pixels[y * self.width + x].color());
+            }
+        }
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < utils::F64_ERROR_MARGIN,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn regular_pattern_takes_centers_of_grid() {
+        let points = SamplePattern::Regular.points(4, &mut Rng::new(0));
+
+        assert_eq!(
+            points,
+            vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+        );
+    }
+
+    #[test]
+    fn grid_patterns_round_count_up_to_square() {
+        let mut rng = Rng::new(0);
+
+        assert_eq!(SamplePattern::Regular.points(1, &mut rng).len(), 1);
+        assert_eq!(SamplePattern::Regular.points(5, &mut rng).len(), 9);
+        assert_eq!(SamplePattern::Jittered.points(9, &mut rng).len(), 9);
+        assert_eq!(SamplePattern::Halton.points(5, &mut rng).len(), 5);
+    }
+
+    #[test]
+    fn jittered_points_stay_in_their_cells() {
+        let mut rng = Rng::new(3);
+
+        for _ in 0..100 {
+            let points = SamplePattern::Jittered.points(4, &mut rng);
+            assert!(points[1].0 >= 0.5 && points[1].0 < 1.0);
+            assert!(points[1].1 >= 0.0 && points[1].1 < 0.5);
+        }
+    }
+
+    #[test]
+    fn halton_sequence() {
+        let base_2: Vec<f64> = (0..4).map(|i| radical_inverse(2, i)).collect();
+        let base_3: Vec<f64> = (0..4).map(|i| radical_inverse(3, i)).collect();
+
+        assert_eq!(base_2, vec![0.0, 0.5, 0.25, 0.75]);
+        for (actual, expected) in base_3
+            .into_iter()
+            .zip([0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0 / 9.0])
+        {
+            assert_close(actual, expected);
+        }
+    }
+
+    #[test]
+    fn sobol_sequence() {
+        let points: Vec<(f64, f64)> = (0..4).map(|i| (sobol_x(i), sobol_y(i))).collect();
+
+        assert_eq!(
+            points,
+            vec![(0.0, 0.0), (0.5, 0.5), (0.25, 0.75), (0.75, 0.25)]
+        );
+    }
+
+    #[test]
+    fn low_discrepancy_points_lie_in_unit_square() {
+        let mut rng = Rng::new(5);
+
+        for pattern in [SamplePattern::Halton, SamplePattern::Sobol] {
+            let points = pattern.points(64, &mut rng);
+            assert!(points
+                .iter()
+                .all(|(u, v)| (0.0..1.0).contains(u) && (0.0..1.0).contains(v)));
+        }
+    }
+
+    #[test]
+    fn filter_weights() {
+        assert_eq!(Filter::Box.weight(0.4, -0.4), 1.0);
+        assert_eq!(Filter::Box.weight(0.6, 0.0), 0.0);
+        assert_eq!(Filter::Tent.weight(0.5, 0.0), 0.5);
+        assert_eq!(Filter::Tent.weight(0.5, 0.5), 0.25);
+        assert_close(Filter::Gaussian.weight(1.5, 0.0), 0.0);
+        assert_close(Filter::Mitchell.weight(0.0, 0.0), (8.0f64 / 9.0).powi(2));
+        assert_close(Filter::Mitchell.weight(1.0, 0.0), 8.0 / 9.0 / 18.0);
+        assert_eq!(Filter::Mitchell.weight(2.5, 0.0), 0.0);
+    }
+
+    #[test]
+    fn mitchell_filter_has_negative_lobes() {
+        assert!(Filter::Mitchell.weight(1.5, 0.0) < 0.0);
+    }
+
+    #[test]
+    fn pixel_samples_stay_inside_pixel() {
+        let sampling = Supersampling::new(16, SamplePattern::Sobol, Filter::Mitchell, 0);
+
+        let samples = sampling.pixel_samples(0);
+
+        assert_eq!(samples.len(), 16);
+        assert!(samples
+            .iter()
+            .all(|(dx, dy)| (-0.5..0.5).contains(dx) && (-0.5..0.5).contains(dy)));
+    }
+
+    #[test]
+    fn samples_per_pixel_tells_how_grid_patterns_round() {
+        let cases = [
+            (SamplePattern::Regular, 5, 9),
+            (SamplePattern::Jittered, 16, 16),
+            (SamplePattern::Halton, 5, 5),
+            (SamplePattern::Sobol, 3, 3),
+        ];
+
+        for (pattern, samples, per_pixel) in cases {
+            let sampling = Supersampling::new(samples, pattern, Filter::Box, 0);
+            assert_eq!(sampling.samples_per_pixel(), per_pixel);
+            assert_eq!(sampling.pixel_samples(0).len(), per_pixel);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one sample")]
+    fn supersampling_needs_samples() {
+        Supersampling::new(0, SamplePattern::Halton, Filter::Box, 0);
+    }
+
+    #[test]
+    fn pixel_samples_are_determined_by_seed_and_pixel() {
+        let sampling = Supersampling::new(4, SamplePattern::Jittered, Filter::Box, 7);
+        let reseeded = Supersampling::new(4, SamplePattern::Jittered, Filter::Box, 8);
+
+        assert_eq!(sampling.pixel_samples(3), sampling.pixel_samples(3));
+        assert_ne!(sampling.pixel_samples(3), sampling.pixel_samples(4));
+        assert_ne!(sampling.pixel_samples(3), reseeded.pixel_samples(3));
+    }
+
+    #[test]
+    fn film_with_box_filter_averages_samples_within_pixel() {
+        let mut film = Film::new(2, 1, Filter::Box);
+
+        film.add_sample(0.25, 0.5, Color::new(1.0, 0.0, 0.0));
+        film.add_sample(0.75, 0.5, Color::new(0.0, 0.0, 1.0));
+        film.add_sample(1.5, 0.5, Color::new(0.0, 1.0, 0.0));
+        let image = film.develop();
+
+        assert_eq!(image.get_pixel(0, 0), Color::new(0.5, 0.0, 0.5));
+        assert_eq!(image.get_pixel(1, 0), Color::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn film_spreads_sample_over_width_of_filter() {
+        let mut film = Film::new(7, 1, Filter::Mitchell);
+
+        film.add_sample(3.5, 0.5, Color::new(1.0, 1.0, 1.0));
+        let image = film.develop();
+
+        assert_eq!(image.get_pixel(0, 0), Color::black());
+        assert_eq!(image.get_pixel(2, 0), Color::new(1.0, 1.0, 1.0));
+        assert_eq!(image.get_pixel(4, 0), Color::new(1.0, 1.0, 1.0));
+        assert_eq!(image.get_pixel(6, 0), Color::black());
+    }
+
+    #[test]
+    fn film_pixel_without_positive_weight_takes_nearest_sample() {
+        let mut film = Film::new(3, 1, Filter::Mitchell);
+
+        // Only the negative lobe of the filter reaches the first pixel.
+        film.add_sample(2.0, 0.5, Color::new(0.2, 0.4, 0.6));
+        let image = film.develop();
+
+        assert_eq!(image.get_pixel(0, 0), Color::new(0.2, 0.4, 0.6));
+    }
+
+    #[test]
+    fn mitchell_film_rings_only_a_little_at_hard_edges() {
+        let (width, height) = (8, 8);
+
+        for samples in 1..=4 {
+            let sampling = Supersampling::new(samples, SamplePattern::Halton, Filter::Mitchell, 0);
+            let mut film = Film::new(width, height, Filter::Mitchell);
+            for y in 0..height {
+                for x in 0..width {
+                    let level = if x < width / 2 { 0.9 } else { 0.1 };
+                    for (dx, dy) in sampling.pixel_samples(y * width + x) {
+                        let (sx, sy) = (x as f64 + 0.5 + dx, y as f64 + 0.5 + dy);
+                        film.add_sample(sx, sy, Color::new(level, level, level));
+                    }
+                }
+            }
+            let image = film.develop();
+
+            // The negative lobes overshoot next to the edge, but by nowhere near the size of
+            // the step, and pixels out of reach of the edge keep the color of their side.
+            for pixel in &image.pixels {
+                assert!(
+                    pixel.red > 0.1 - 0.2 && pixel.red < 0.9 + 0.2,
+                    "{:?}",
+                    pixel
+                );
+            }
+            assert_close(image.get_pixel(0, 4).red, 0.9);
+            assert_close(image.get_pixel(7, 4).red, 0.1);
+        }
+    }
+}
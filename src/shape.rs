@@ -0,0 +1,225 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::tuple::Tuple;
+
+/// Anything that can be placed in a world and hit by a ray.
+///
+/// Implementors only describe themselves in object space through `local_intersect` and
+/// `local_normal_at`; moving rays and normals between world and object space is shared.
+pub trait Shape: std::fmt::Debug {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_>;
+    fn local_normal_at(&self, point: &Tuple) -> Tuple;
+
+    /// The box around the shape in object space.
+    fn bounds(&self) -> BoundingBox;
+
+    fn get_transform(&self) -> &Matrix;
+    fn get_inverse_transform(&self) -> &Matrix;
+    fn set_transform(&mut self, transform: &Matrix);
+
+    /// The inverse of everything between world space and this shape's parent, i.e. the
+    /// composed inverse transforms of all enclosing groups. The identity for a shape that
+    /// sits directly in the world.
+    fn get_parent_inverse_transform(&self) -> &Matrix;
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix);
+
+    fn get_material(&self) -> &Material;
+    fn set_material(&mut self, material: &Material);
+
+    /// Like `local_normal_at`, but with access to the hit that produced `point`. Shapes that
+    /// interpolate their normal across the surface use the hit's `u` and `v` for that.
+    fn local_normal_at_hit(&self, point: &Tuple, _hit: &Intersection) -> Tuple {
+        self.local_normal_at(point)
+    }
+
+    /// The box around the shape in the space of its parent, i.e. after its own transform.
+    fn parent_space_bounds(&self) -> BoundingBox {
+        self.bounds().transform(self.get_transform())
+    }
+
+    /// Organizes whatever the shape contains into a bounding volume hierarchy, splitting
+    /// until no group holds more than `threshold` shapes. Only groups contain anything.
+    fn divide(&mut self, _threshold: usize) {}
+
+    /// Whether `other` is this shape or, for shapes that contain others, anything inside it.
+    fn includes(&self, other: &dyn Shape) -> bool {
+        std::ptr::addr_eq(self, other)
+    }
+
+    fn normal_at(&self, point: &Tuple) -> Tuple {
+        let object_point = self.world_to_object(point);
+        self.normal_to_world(&self.local_normal_at(&object_point))
+    }
+
+    fn normal_at_hit(&self, point: &Tuple, hit: &Intersection) -> Tuple {
+        let object_point = self.world_to_object(point);
+        self.normal_to_world(&self.local_normal_at_hit(&object_point, hit))
+    }
+
+    fn world_to_object(&self, point: &Tuple) -> Tuple {
+        let parent_point = self.get_parent_inverse_transform() * point;
+        self.get_inverse_transform() * parent_point
+    }
+
+    fn normal_to_world(&self, normal: &Tuple) -> Tuple {
+        let mut parent_normal = &self.get_inverse_transform().transpose() * normal;
+
+        // When transforming the normal, we mess up the "vector"-status of the normal.
+        // Resetting that here, and again after each step out through the enclosing groups.
+        parent_normal.w = 0;
+        let mut world_normal = &self.get_parent_inverse_transform().transpose() * parent_normal;
+        world_normal.w = 0;
+        world_normal.norm()
+    }
+}
+
+/// Whether `a` and `b` are the very same shape, as opposed to two shapes that merely look alike.
+pub fn same_shape(a: &dyn Shape, b: &dyn Shape) -> bool {
+    std::ptr::addr_eq(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformations::{rotation_z, scaling, translation};
+    use std::cell::RefCell;
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    /// A shape that records the object-space ray it was asked to intersect.
+    #[derive(Debug)]
+    struct TestShape {
+        transform: Matrix,
+        inverse_transform: Matrix,
+        parent_inverse_transform: Matrix,
+        material: Material,
+        saved_ray: RefCell<Option<Ray>>,
+    }
+
+    impl TestShape {
+        fn new() -> Self {
+            Self {
+                transform: Matrix::identity(4),
+                inverse_transform: Matrix::identity(4),
+                parent_inverse_transform: Matrix::identity(4),
+                material: Material::default(),
+                saved_ray: RefCell::new(None),
+            }
+        }
+    }
+
+    impl Shape for TestShape {
+        fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+            self.saved_ray.replace(Some(ray.clone()));
+            Intersections::new(vec![Intersection::new(1.0, self)])
+        }
+
+        fn local_normal_at(&self, point: &Tuple) -> Tuple {
+            Tuple::vector(point.x, point.y, point.z)
+        }
+
+        fn bounds(&self) -> BoundingBox {
+            BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+        }
+
+        fn get_transform(&self) -> &Matrix {
+            &self.transform
+        }
+
+        fn get_inverse_transform(&self) -> &Matrix {
+            &self.inverse_transform
+        }
+
+        fn set_transform(&mut self, transform: &Matrix) {
+            self.transform = transform.clone();
+            self.inverse_transform = transform.inverse();
+        }
+
+        fn get_parent_inverse_transform(&self) -> &Matrix {
+            &self.parent_inverse_transform
+        }
+
+        fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+            self.parent_inverse_transform = parent_inverse.clone();
+        }
+
+        fn get_material(&self) -> &Material {
+            &self.material
+        }
+
+        fn set_material(&mut self, material: &Material) {
+            self.material = material.clone();
+        }
+    }
+
+    #[test]
+    fn intersect_scaled_shape_with_ray() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let mut s = TestShape::new();
+        s.set_transform(&scaling(2.0, 2.0, 2.0));
+
+        r.intersect(&s);
+
+        let saved_ray = s.saved_ray.borrow().clone().unwrap();
+        assert_eq!(saved_ray.origin, Tuple::point(0.0, 0.0, -2.5));
+        assert_eq!(saved_ray.direction, Tuple::vector(0.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn intersect_translated_shape_with_ray() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let mut s = TestShape::new();
+        s.set_transform(&translation(5.0, 0.0, 0.0));
+
+        r.intersect(&s);
+
+        let saved_ray = s.saved_ray.borrow().clone().unwrap();
+        assert_eq!(saved_ray.origin, Tuple::point(-5.0, 0.0, -5.0));
+        assert_eq!(saved_ray.direction, Tuple::vector(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn normal_on_translated_shape() {
+        let mut s = TestShape::new();
+        s.set_transform(&translation(0.0, 1.0, 0.0));
+
+        let n = s.normal_at(&Tuple::point(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+
+        assert_eq!(n, Tuple::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn normal_on_transformed_shape() {
+        let mut s = TestShape::new();
+        s.set_transform(&(scaling(1.0, 0.5, 1.0) * rotation_z(PI / 5.0)));
+
+        let n = s.normal_at(&Tuple::point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+
+        assert_eq!(
+            n,
+            Tuple::vector(0.0, 0.9701425001453319, -0.24253562503633294)
+        );
+    }
+
+    #[test]
+    fn parent_space_bounds_of_shape() {
+        let mut s = TestShape::new();
+        s.set_transform(&(translation(1.0, -3.0, 5.0) * scaling(0.5, 2.0, 4.0)));
+
+        let b = s.parent_space_bounds();
+
+        assert_eq!(b.min, Tuple::point(0.5, -5.0, 1.0));
+        assert_eq!(b.max, Tuple::point(1.5, -1.0, 9.0));
+    }
+
+    #[test]
+    fn shapes_are_compared_by_identity() {
+        let a = TestShape::new();
+        let b = TestShape::new();
+
+        assert!(same_shape(&a, &a));
+        assert!(!same_shape(&a, &b));
+    }
+}
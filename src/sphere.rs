@@ -1,9 +1,9 @@
+use crate::bounds::BoundingBox;
 use crate::intersection::{Intersection, Intersections};
 use crate::material::Material;
 use crate::matrix::Matrix;
-use crate::normals::Normal;
-use crate::ray;
-use crate::ray::Intersect;
+use crate::ray::Ray;
+use crate::shape::Shape;
 use crate::tuple::Tuple;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -11,6 +11,8 @@ pub struct Sphere {
     radius: f64,
     origin: Tuple,
     transform: Matrix,
+    inverse_transform: Matrix,
+    parent_inverse_transform: Matrix,
     material: Material,
 }
 
@@ -20,40 +22,27 @@ impl Sphere {
             radius,
             origin,
             transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            parent_inverse_transform: Matrix::identity(4),
             material: Material::default(),
         }
     }
 
-    pub(crate) fn set_transform(&mut self, transform: &Matrix) {
-        self.transform = transform.clone();
-    }
-
-    pub fn set_material(&mut self, material: &Material) {
-        self.material = *material
-    }
-
     pub fn unit() -> Self {
         Sphere::new(1.0, Tuple::origin())
     }
-}
 
-impl Normal for Sphere {
-    fn normal_at(&self, point: &Tuple) -> Tuple {
-        let transform = self.transform.clone();
-        let transform_inverse = transform.inverse();
-        let object_point = transform_inverse.clone() * *point;
-        let object_normal = object_point - self.origin;
-        let mut world_normal = transform_inverse.transpose() * object_normal;
-
-        // When transforming the normal, we mess up the "vector"-status of the normal.
-        // Resetting that here.
-        world_normal.w = 0;
-        world_normal.norm()
+    /// A unit sphere of clear glass.
+    pub fn glass() -> Self {
+        let mut sphere = Sphere::unit();
+        sphere.material.transparency = 1.0;
+        sphere.material.refractive_index = 1.5;
+        sphere
     }
 }
 
-impl Intersect for Sphere {
-    fn ray_intersections(&self, ray: &ray::Ray) -> Intersections {
+impl Shape for Sphere {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
         let sphere_to_ray = ray.origin - self.origin;
 
         let a = ray.direction.dot(&ray.direction);
@@ -71,26 +60,49 @@ impl Intersect for Sphere {
             let r2 = (-b + disc_sqrt) / (denom);
 
             Intersections {
-                objects: vec![
-                    Intersection {
-                        time: r1,
-                        object: self.clone(),
-                    },
-                    Intersection {
-                        time: r2,
-                        object: self.clone(),
-                    },
-                ],
+                objects: vec![Intersection::new(r1, self), Intersection::new(r2, self)],
             }
         }
     }
 
-    fn get_transform(&self) -> Option<Matrix> {
-        Option::from(self.transform.clone())
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        *point - self.origin
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            self.origin - Tuple::vector(1.0, 1.0, 1.0),
+            self.origin + Tuple::vector(1.0, 1.0, 1.0),
+        )
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+
+    fn get_parent_inverse_transform(&self) -> &Matrix {
+        &self.parent_inverse_transform
+    }
+
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+        self.parent_inverse_transform = parent_inverse.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
     }
 
-    fn get_material(&self) -> Option<Material> {
-        Option::from(self.material.clone())
+    fn set_material(&mut self, material: &Material) {
+        self.material = material.clone()
     }
 }
 
@@ -110,7 +122,7 @@ mod tests {
     #[test]
     fn test_sphere_set_transform() {
         let mut s = Sphere::unit();
-        let t = scaling(1.0, 2.0, 0.0);
+        let t = scaling(1.0, 2.0, 3.0);
 
         s.set_transform(&t);
 
@@ -148,6 +160,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_sphere_normal_after_translate() {
         let mut s = Sphere::unit();
         s.set_transform(&translation(0.0, 1.0, 0.0));
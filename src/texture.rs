@@ -0,0 +1,472 @@
+use crate::canvas::Canvas;
+use crate::color::Color;
+use crate::matrix::Matrix;
+use crate::pattern::Pattern;
+use crate::tuple::Tuple;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+/// How a point on a shape in object space is flattened to (u, v) texture coordinates, both
+/// in [0, 1], with v = 0 at the bottom of the texture.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UvMapping {
+    /// Around a unit sphere at the origin, with u running along the equator.
+    Spherical,
+    /// The xz-plane, repeating the texture every unit.
+    Planar,
+    /// Around a cylinder along the y-axis, repeating the texture every unit of height.
+    Cylindrical,
+    /// The six faces of the unit cube, unfolded into a horizontal cross: the top row holds
+    /// the up face, the middle row left, front, right and back, and the bottom row down.
+    Cube,
+}
+
+impl UvMapping {
+    pub fn map(&self, point: &Tuple) -> (f64, f64) {
+        match self {
+            UvMapping::Spherical => spherical_map(point),
+            UvMapping::Planar => (point.x.rem_euclid(1.0), point.z.rem_euclid(1.0)),
+            UvMapping::Cylindrical => cylindrical_map(point),
+            UvMapping::Cube => cube_map(point),
+        }
+    }
+}
+
+/// The angle around the y-axis as a fraction of a full turn, increasing counter-clockwise
+/// when seen from above and starting at the negative z-axis.
+fn azimuth(point: &Tuple) -> f64 {
+    let theta = point.x.atan2(point.z);
+    let raw_u = theta / (2.0 * PI);
+    1.0 - (raw_u + 0.5)
+}
+
+fn spherical_map(point: &Tuple) -> (f64, f64) {
+    let radius = Tuple::vector(point.x, point.y, point.z).abs();
+    if radius == 0.0 {
+        // The center of the sphere has no direction, so pick the middle of the texture.
+        return (0.5, 0.5);
+    }
+    let phi = (point.y / radius).clamp(-1.0, 1.0).acos();
+
+    (azimuth(point), 1.0 - phi / PI)
+}
+
+fn cylindrical_map(point: &Tuple) -> (f64, f64) {
+    (azimuth(point), point.y.rem_euclid(1.0))
+}
+
+fn cube_map(point: &Tuple) -> (f64, f64) {
+    let (x, y, z) = (point.x, point.y, point.z);
+    let face_coordinate = |c: f64| (c + 1.0).rem_euclid(2.0) / 2.0;
+    let flipped_coordinate = |c: f64| (1.0 - c).rem_euclid(2.0) / 2.0;
+    let largest = x.abs().max(y.abs()).max(z.abs());
+
+    // Which cell of the 4x3 cross the face is, counted from the bottom left, and where on
+    // that face the point lies.
+    let ((column, row), (u, v)) = if largest == x {
+        ((2.0, 1.0), (flipped_coordinate(z), face_coordinate(y)))
+    } else if largest == -x {
+        ((0.0, 1.0), (face_coordinate(z), face_coordinate(y)))
+    } else if largest == y {
+        ((1.0, 2.0), (face_coordinate(x), flipped_coordinate(z)))
+    } else if largest == -y {
+        ((1.0, 0.0), (face_coordinate(x), face_coordinate(z)))
+    } else if largest == z {
+        ((1.0, 1.0), (face_coordinate(x), face_coordinate(y)))
+    } else {
+        ((3.0, 1.0), (flipped_coordinate(x), face_coordinate(y)))
+    };
+
+    ((column + u) / 4.0, (row + v) / 3.0)
+}
+
+/// A color that varies over the unit square of texture coordinates.
+pub trait UvTexture: std::fmt::Debug {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color;
+}
+
+/// A checkerboard of `width` by `height` squares, mostly for checking mappings by eye.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UvChecker {
+    pub width: usize,
+    pub height: usize,
+    pub a: Color,
+    pub b: Color,
+}
+
+impl UvChecker {
+    pub fn new(width: usize, height: usize, a: Color, b: Color) -> Self {
+        UvChecker {
+            width,
+            height,
+            a,
+            b,
+        }
+    }
+}
+
+impl UvTexture for UvChecker {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+        let u2 = (u * self.width as f64).floor();
+        let v2 = (v * self.height as f64).floor();
+
+        if (u2 + v2).rem_euclid(2.0) == 0.0 {
+            self.a
+        } else {
+            self.b
+        }
+    }
+}
+
+/// A picture, sampled with bilinear filtering between its pixels. The texture wraps around
+/// horizontally, so that the seam of a spherical or cylindrical mapping does not show.
+#[derive(Debug, Clone)]
+pub struct ImageTexture {
+    canvas: Canvas,
+}
+
+impl ImageTexture {
+    /// Panics if `canvas` has no pixels, since there would be nothing to sample.
+    pub fn new(canvas: Canvas) -> Self {
+        assert!(
+            canvas.width > 0 && canvas.height > 0,
+            "an image texture needs at least one pixel"
+        );
+
+        ImageTexture { canvas }
+    }
+
+    pub fn width(&self) -> usize {
+        self.canvas.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.canvas.height
+    }
+}
+
+impl UvTexture for ImageTexture {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+        let (width, height) = (self.canvas.width, self.canvas.height);
+
+        // Pixel centers sit at half-integer coordinates, and the top row of the image is v = 1.
+        let x = u.rem_euclid(1.0) * width as f64 - 0.5;
+        let y = ((1.0 - v) * height as f64 - 0.5).clamp(0.0, (height - 1) as f64);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+
+        let column = |x: f64| (x as i64).rem_euclid(width as i64) as usize;
+        let (left, right) = (column(x0), column(x0 + 1.0));
+        let (top, bottom) = (y0 as usize, (y0 as usize + 1).min(height - 1));
+
+        let upper =
+            self.canvas.get_pixel(left, top) * (1.0 - tx) + self.canvas.get_pixel(right, top) * tx;
+        let lower = self.canvas.get_pixel(left, bottom) * (1.0 - tx)
+            + self.canvas.get_pixel(right, bottom) * tx;
+        upper * (1.0 - ty) + lower * ty
+    }
+}
+
+/// Loads a PNG, JPEG or any other format the `image` crate can decode as a texture. HDR
+/// images keep their full range, so they can light a scene as an `Environment`.
+pub fn read_image_texture(path: &str) -> Result<ImageTexture, ::image::ImageError> {
+    let picture = ::image::open(path)?.into_rgb32f();
+    if picture.width() == 0 || picture.height() == 0 {
+        return Err(::image::ImageError::Parameter(
+            ::image::error::ParameterError::from_kind(::image::error::ParameterErrorKind::Generic(
+                "the image has no pixels".to_string(),
+            )),
+        ));
+    }
+
+    let mut canvas = Canvas::new(picture.width() as usize, picture.height() as usize);
+    for (x, y, pixel) in picture.enumerate_pixels() {
+        let [red, green, blue] = pixel.0;
+        canvas.write_pixel(
+            x as usize,
+            y as usize,
+            &Color::new(red as f64, green as f64, blue as f64),
+        );
+    }
+
+    Ok(ImageTexture::new(canvas))
+}
+
+/// A pattern that wraps a texture around a shape by the given mapping.
+#[derive(Debug, Clone)]
+pub struct TextureMap {
+    pub texture: Rc<dyn UvTexture>,
+    pub mapping: UvMapping,
+    transform: Matrix,
+    inverse_transform: Matrix,
+}
+
+impl TextureMap {
+    pub fn new(texture: Rc<dyn UvTexture>, mapping: UvMapping) -> Self {
+        TextureMap {
+            texture,
+            mapping,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+        }
+    }
+}
+
+impl Pattern for TextureMap {
+    fn local_pattern_at(&self, point: &Tuple) -> Color {
+        let (u, v) = self.mapping.map(point);
+        self.texture.uv_pattern_at(u, v)
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    fn white() -> Color {
+        Color::new(1.0, 1.0, 1.0)
+    }
+
+    fn black() -> Color {
+        Color::black()
+    }
+
+    fn assert_uv(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < utils::F64_ERROR_MARGIN
+                && (actual.1 - expected.1).abs() < utils::F64_ERROR_MARGIN,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn checker_in_uv_space() {
+        let checker = UvChecker::new(2, 2, black(), white());
+        let cases = [
+            (0.0, 0.0, black()),
+            (0.5, 0.0, white()),
+            (0.0, 0.5, white()),
+            (0.5, 0.5, black()),
+            (1.0, 1.0, black()),
+        ];
+
+        for (u, v, color) in cases {
+            assert_eq!(checker.uv_pattern_at(u, v), color);
+        }
+    }
+
+    #[test]
+    fn spherical_mapping_on_unit_sphere() {
+        let cases = [
+            (Tuple::point(0.0, 0.0, -1.0), (0.0, 0.5)),
+            (Tuple::point(1.0, 0.0, 0.0), (0.25, 0.5)),
+            (Tuple::point(0.0, 0.0, 1.0), (0.5, 0.5)),
+            (Tuple::point(-1.0, 0.0, 0.0), (0.75, 0.5)),
+            (Tuple::point(0.0, 1.0, 0.0), (0.5, 1.0)),
+            (Tuple::point(0.0, -1.0, 0.0), (0.5, 0.0)),
+            (
+                Tuple::point(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0),
+                (0.25, 0.75),
+            ),
+        ];
+
+        for (point, uv) in cases {
+            assert_uv(UvMapping::Spherical.map(&point), uv);
+        }
+    }
+
+    #[test]
+    fn spherical_mapping_of_center_is_fixed() {
+        assert_uv(UvMapping::Spherical.map(&Tuple::origin()), (0.5, 0.5));
+    }
+
+    #[test]
+    fn planar_mapping_repeats_every_unit() {
+        let cases = [
+            (Tuple::point(0.25, 0.0, 0.5), (0.25, 0.5)),
+            (Tuple::point(0.25, 0.0, -0.25), (0.25, 0.75)),
+            (Tuple::point(0.25, 0.5, -0.25), (0.25, 0.75)),
+            (Tuple::point(1.25, 0.0, 0.5), (0.25, 0.5)),
+            (Tuple::point(0.25, 0.0, -1.75), (0.25, 0.25)),
+            (Tuple::point(1.0, 0.0, -1.0), (0.0, 0.0)),
+            (Tuple::origin(), (0.0, 0.0)),
+        ];
+
+        for (point, uv) in cases {
+            assert_uv(UvMapping::Planar.map(&point), uv);
+        }
+    }
+
+    #[test]
+    fn cylindrical_mapping_on_unit_cylinder() {
+        let cases = [
+            (Tuple::point(0.0, 0.0, -1.0), (0.0, 0.0)),
+            (Tuple::point(0.0, 0.5, -1.0), (0.0, 0.5)),
+            (Tuple::point(0.0, 1.0, -1.0), (0.0, 0.0)),
+            (
+                Tuple::point(FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2),
+                (0.125, 0.5),
+            ),
+            (Tuple::point(1.0, 0.5, 0.0), (0.25, 0.5)),
+            (
+                Tuple::point(FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2),
+                (0.375, 0.5),
+            ),
+            (Tuple::point(0.0, -0.25, 1.0), (0.5, 0.75)),
+            (Tuple::point(-1.0, 1.25, 0.0), (0.75, 0.25)),
+        ];
+
+        for (point, uv) in cases {
+            assert_uv(UvMapping::Cylindrical.map(&point), uv);
+        }
+    }
+
+    #[test]
+    fn cube_mapping_unfolds_into_cross() {
+        let cases = [
+            // The centers of the front, back, left, right, up and down faces.
+            (Tuple::point(0.0, 0.0, 1.0), (0.375, 0.5)),
+            (Tuple::point(0.0, 0.0, -1.0), (0.875, 0.5)),
+            (Tuple::point(-1.0, 0.0, 0.0), (0.125, 0.5)),
+            (Tuple::point(1.0, 0.0, 0.0), (0.625, 0.5)),
+            (Tuple::point(0.0, 1.0, 0.0), (0.375, 2.5 / 3.0)),
+            (Tuple::point(0.0, -1.0, 0.0), (0.375, 0.5 / 3.0)),
+            // Corners of the front face.
+            (
+                Tuple::point(-0.9, -0.9, 1.0),
+                (0.2625, 0.05 / 3.0 + 1.0 / 3.0),
+            ),
+            (Tuple::point(0.9, 0.9, 1.0), (0.4875, 1.95 / 3.0)),
+            // The up face, near its edge with the front face.
+            (Tuple::point(-0.9, 1.0, 0.9), (0.2625, 2.05 / 3.0)),
+        ];
+
+        for (point, uv) in cases {
+            assert_uv(UvMapping::Cube.map(&point), uv);
+        }
+    }
+
+    /// A 2x2 image: red and green on top, blue and white below.
+    fn quad_texture() -> ImageTexture {
+        let mut canvas = Canvas::new(2, 2);
+        canvas.write_pixel(0, 0, &Color::new(1.0, 0.0, 0.0));
+        canvas.write_pixel(1, 0, &Color::new(0.0, 1.0, 0.0));
+        canvas.write_pixel(0, 1, &Color::new(0.0, 0.0, 1.0));
+        canvas.write_pixel(1, 1, &white());
+        ImageTexture::new(canvas)
+    }
+
+    #[test]
+    fn image_texture_hits_pixel_centers_exactly() {
+        let texture = quad_texture();
+
+        assert_eq!(texture.uv_pattern_at(0.25, 0.75), Color::new(1.0, 0.0, 0.0));
+        assert_eq!(texture.uv_pattern_at(0.75, 0.75), Color::new(0.0, 1.0, 0.0));
+        assert_eq!(texture.uv_pattern_at(0.25, 0.25), Color::new(0.0, 0.0, 1.0));
+        assert_eq!(texture.uv_pattern_at(0.75, 0.25), white());
+    }
+
+    #[test]
+    fn image_texture_blends_between_pixels() {
+        let texture = quad_texture();
+
+        assert_eq!(texture.uv_pattern_at(0.5, 0.75), Color::new(0.5, 0.5, 0.0));
+        assert_eq!(texture.uv_pattern_at(0.5, 0.5), Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn image_texture_wraps_horizontally_and_clamps_vertically() {
+        let texture = quad_texture();
+
+        // Halfway between the right and left column, across the seam.
+        assert_eq!(texture.uv_pattern_at(0.0, 0.75), Color::new(0.5, 0.5, 0.0));
+        assert_eq!(texture.uv_pattern_at(0.25, 1.0), Color::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn read_texture_from_png() {
+        let path = std::env::temp_dir().join("ray_tracer_texture_test.png");
+        let mut picture = ::image::RgbImage::new(2, 1);
+        picture.put_pixel(0, 0, ::image::Rgb([255, 0, 0]));
+        picture.put_pixel(1, 0, ::image::Rgb([0, 0, 255]));
+        picture.save(&path).unwrap();
+
+        let texture = read_image_texture(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(texture.width(), 2);
+        assert_eq!(texture.height(), 1);
+        assert_eq!(texture.uv_pattern_at(0.25, 0.5), Color::new(1.0, 0.0, 0.0));
+        assert_eq!(texture.uv_pattern_at(0.75, 0.5), Color::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn read_texture_from_hdr_keeps_bright_values() {
+        let path = std::env::temp_dir().join("ray_tracer_texture_test.hdr");
+        let file = std::fs::File::create(&path).unwrap();
+        ::image::codecs::hdr::HdrEncoder::new(file)
+            .encode(&[::image::Rgb([4.0, 2.0, 0.5])], 1, 1)
+            .unwrap();
+
+        let texture = read_image_texture(path.to_str().unwrap()).unwrap();
+
+        assert_eq!(texture.uv_pattern_at(0.5, 0.5), Color::new(4.0, 2.0, 0.5));
+    }
+
+    #[test]
+    fn empty_texture_file_is_an_error() {
+        let path = std::env::temp_dir().join("ray_tracer_empty_texture_test.hdr");
+        std::fs::write(&path, "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 0 +X 4\n").unwrap();
+
+        assert!(read_image_texture(path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one pixel")]
+    fn image_texture_needs_pixels() {
+        ImageTexture::new(Canvas::new(0, 3));
+    }
+
+    #[test]
+    fn missing_texture_file_is_an_error() {
+        assert!(read_image_texture("no/such/texture.png").is_err());
+    }
+
+    #[test]
+    fn texture_map_wraps_texture_around_sphere() {
+        let checker = UvChecker::new(16, 8, black(), white());
+        let pattern = TextureMap::new(Rc::new(checker), UvMapping::Spherical);
+        let cases = [
+            (Tuple::point(0.4315, 0.4670, 0.7719), white()),
+            (Tuple::point(-0.9654, 0.2552, -0.0534), black()),
+            (Tuple::point(0.1039, 0.7090, 0.6975), white()),
+            (Tuple::point(-0.4986, -0.7856, -0.3663), black()),
+            (Tuple::point(-0.0317, -0.9395, 0.3411), black()),
+            (Tuple::point(0.4809, -0.7721, 0.4154), black()),
+            (Tuple::point(0.0285, -0.9612, -0.2745), black()),
+            (Tuple::point(-0.5734, -0.2162, -0.7903), white()),
+            (Tuple::point(0.7688, -0.1470, 0.6223), black()),
+            (Tuple::point(-0.7652, 0.2175, 0.6060), black()),
+        ];
+
+        for (point, color) in cases {
+            assert_eq!(pattern.local_pattern_at(&point), color);
+        }
+    }
+}
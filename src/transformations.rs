@@ -1,4 +1,5 @@
 use crate::matrix::Matrix;
+use crate::tuple::Tuple;
 
 pub fn translation(x: f64, y: f64, z: f64) -> Matrix {
     let mut t = Matrix::identity(4);
@@ -60,6 +61,24 @@ pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix
     t
 }
 
+/// Orients the world relative to an eye at `from`, looking towards `to`, with `up` pointing roughly upwards.
+pub fn view_transform(from: &Tuple, to: &Tuple, up: &Tuple) -> Matrix {
+    let forward = (*to - *from).norm();
+    let left = forward.cross(&up.norm());
+    let true_up = left.cross(&forward);
+
+    let orientation = Matrix::new(
+        vec![
+            left.x, left.y, left.z, 0.0, true_up.x, true_up.y, true_up.z, 0.0, -forward.x,
+            -forward.y, -forward.z, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ],
+        4,
+        4,
+    );
+
+    orientation * translation(-from.x, -from.y, -from.z)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::transformations::{rotation_x, scaling, translation};
@@ -267,4 +286,72 @@ mod tests {
 
         assert_eq!(c * b * a * p, Tuple::point(15.0, 0.0, 7.0));
     }
+
+    mod view_transform {
+        use crate::matrix::Matrix;
+        use crate::transformations::{scaling, translation, view_transform};
+        use crate::tuple::Tuple;
+
+        #[test]
+        fn default_orientation() {
+            let from = Tuple::origin();
+            let to = Tuple::point(0.0, 0.0, -1.0);
+            let up = Tuple::vector(0.0, 1.0, 0.0);
+
+            assert_eq!(view_transform(&from, &to, &up), Matrix::identity(4));
+        }
+
+        #[test]
+        fn looking_in_positive_z_direction() {
+            let from = Tuple::origin();
+            let to = Tuple::point(0.0, 0.0, 1.0);
+            let up = Tuple::vector(0.0, 1.0, 0.0);
+
+            assert_eq!(view_transform(&from, &to, &up), scaling(-1.0, 1.0, -1.0));
+        }
+
+        #[test]
+        fn view_transform_moves_the_world() {
+            let from = Tuple::point(0.0, 0.0, 8.0);
+            let to = Tuple::origin();
+            let up = Tuple::vector(0.0, 1.0, 0.0);
+
+            assert_eq!(view_transform(&from, &to, &up), translation(0.0, 0.0, -8.0));
+        }
+
+        #[test]
+        fn arbitrary_view_transform() {
+            let from = Tuple::point(1.0, 3.0, 2.0);
+            let to = Tuple::point(4.0, -2.0, 8.0);
+            let up = Tuple::vector(1.0, 1.0, 0.0);
+
+            let t = view_transform(&from, &to, &up);
+
+            assert_eq!(
+                t,
+                Matrix::new(
+                    vec![
+                        -0.5070925528371099,
+                        0.5070925528371099,
+                        0.6761234037828132,
+                        -2.366431913239846,
+                        0.7677159338596801,
+                        0.6060915267313263,
+                        0.12121830534626524,
+                        -2.8284271247461894,
+                        -0.35856858280031806,
+                        0.5976143046671968,
+                        -0.7171371656006361,
+                        0.0,
+                        0.0,
+                        0.0,
+                        0.0,
+                        1.0,
+                    ],
+                    4,
+                    4
+                )
+            );
+        }
+    }
 }
@@ -0,0 +1,381 @@
+use crate::bounds::BoundingBox;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::tuple::Tuple;
+use crate::utils;
+
+/// A flat triangle between three points, with its edges and face normal precomputed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Triangle {
+    pub p1: Tuple,
+    pub p2: Tuple,
+    pub p3: Tuple,
+    pub e1: Tuple,
+    pub e2: Tuple,
+    pub normal: Tuple,
+    transform: Matrix,
+    inverse_transform: Matrix,
+    parent_inverse_transform: Matrix,
+    material: Material,
+}
+
+impl Triangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple) -> Self {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+
+        Triangle {
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross(&e1).norm(),
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            parent_inverse_transform: Matrix::identity(4),
+            material: Material::default(),
+        }
+    }
+}
+
+/// A triangle whose normal is interpolated between the normals given at each vertex,
+/// so that a mesh of them looks smoothly curved.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SmoothTriangle {
+    pub p1: Tuple,
+    pub p2: Tuple,
+    pub p3: Tuple,
+    pub n1: Tuple,
+    pub n2: Tuple,
+    pub n3: Tuple,
+    pub e1: Tuple,
+    pub e2: Tuple,
+    transform: Matrix,
+    inverse_transform: Matrix,
+    parent_inverse_transform: Matrix,
+    material: Material,
+}
+
+impl SmoothTriangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple, n1: Tuple, n2: Tuple, n3: Tuple) -> Self {
+        SmoothTriangle {
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+            transform: Matrix::identity(4),
+            inverse_transform: Matrix::identity(4),
+            parent_inverse_transform: Matrix::identity(4),
+            material: Material::default(),
+        }
+    }
+
+    fn interpolate_normal(&self, u: f64, v: f64) -> Tuple {
+        self.n2 * u + self.n3 * v + self.n1 * (1.0 - u - v)
+    }
+}
+
+/// Intersects a ray with the triangle spanned by `e1` and `e2` from `p1` using the
+/// Möller–Trumbore algorithm, returning the time and the barycentric u and v of the hit.
+fn intersect_triangle(p1: &Tuple, e1: &Tuple, e2: &Tuple, ray: &Ray) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = ray.direction.cross(e2);
+    let determinant = e1.dot(&dir_cross_e2);
+    if determinant.abs() <= utils::PARALLEL_EPSILON * e1.abs() * e2.abs() * ray.direction.abs() {
+        return None;
+    }
+
+    let f = 1.0 / determinant;
+    let p1_to_origin = ray.origin - *p1;
+    let u = f * p1_to_origin.dot(&dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = p1_to_origin.cross(e1);
+    let v = f * ray.direction.dot(&origin_cross_e1);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    Some((f * e2.dot(&origin_cross_e1), u, v))
+}
+
+impl Shape for Triangle {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        match intersect_triangle(&self.p1, &self.e1, &self.e2, ray) {
+            None => Intersections { objects: vec![] },
+            Some((time, u, v)) => Intersections {
+                objects: vec![Intersection::with_uv(time, self, u, v)],
+            },
+        }
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        self.normal
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        for point in [self.p1, self.p2, self.p3] {
+            bounds.add_point(&point);
+        }
+        bounds
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+
+    fn get_parent_inverse_transform(&self) -> &Matrix {
+        &self.parent_inverse_transform
+    }
+
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+        self.parent_inverse_transform = parent_inverse.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: &Material) {
+        self.material = material.clone()
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        match intersect_triangle(&self.p1, &self.e1, &self.e2, ray) {
+            None => Intersections { objects: vec![] },
+            Some((time, u, v)) => Intersections {
+                objects: vec![Intersection::with_uv(time, self, u, v)],
+            },
+        }
+    }
+
+    /// Without a hit we recover u and v from the point itself.
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        let p1_to_point = *point - self.p1;
+        let d11 = self.e1.dot(&self.e1);
+        let d12 = self.e1.dot(&self.e2);
+        let d22 = self.e2.dot(&self.e2);
+        let dp1 = p1_to_point.dot(&self.e1);
+        let dp2 = p1_to_point.dot(&self.e2);
+        let denominator = d11 * d22 - d12 * d12;
+
+        let u = (d22 * dp1 - d12 * dp2) / denominator;
+        let v = (d11 * dp2 - d12 * dp1) / denominator;
+        self.interpolate_normal(u, v)
+    }
+
+    fn local_normal_at_hit(&self, _point: &Tuple, hit: &Intersection) -> Tuple {
+        self.interpolate_normal(hit.u, hit.v)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::empty();
+        for point in [self.p1, self.p2, self.p3] {
+            bounds.add_point(&point);
+        }
+        bounds
+    }
+
+    fn get_transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    fn get_inverse_transform(&self) -> &Matrix {
+        &self.inverse_transform
+    }
+
+    fn set_transform(&mut self, transform: &Matrix) {
+        self.transform = transform.clone();
+        self.inverse_transform = transform.inverse();
+    }
+
+    fn get_parent_inverse_transform(&self) -> &Matrix {
+        &self.parent_inverse_transform
+    }
+
+    fn set_parent_inverse_transform(&mut self, parent_inverse: &Matrix) {
+        self.parent_inverse_transform = parent_inverse.clone();
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn set_material(&mut self, material: &Material) {
+        self.material = material.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Tuple::point(0.0, 1.0, 0.0),
+            Tuple::point(-1.0, 0.0, 0.0),
+            Tuple::point(1.0, 0.0, 0.0),
+        )
+    }
+
+    fn smooth_triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            Tuple::point(0.0, 1.0, 0.0),
+            Tuple::point(-1.0, 0.0, 0.0),
+            Tuple::point(1.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+            Tuple::vector(-1.0, 0.0, 0.0),
+            Tuple::vector(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn construct_triangle() {
+        let t = triangle();
+
+        assert_eq!(t.e1, Tuple::vector(-1.0, -1.0, 0.0));
+        assert_eq!(t.e2, Tuple::vector(1.0, -1.0, 0.0));
+        assert_eq!(t.normal, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn normal_of_triangle_is_constant() {
+        let t = triangle();
+
+        assert_eq!(t.local_normal_at(&Tuple::point(0.0, 0.5, 0.0)), t.normal);
+        assert_eq!(t.local_normal_at(&Tuple::point(-0.5, 0.75, 0.0)), t.normal);
+        assert_eq!(t.local_normal_at(&Tuple::point(0.5, 0.25, 0.0)), t.normal);
+    }
+
+    #[test]
+    fn ray_parallel_to_triangle() {
+        let t = triangle();
+        let r = Ray::new(Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        assert_eq!(t.local_intersect(&r).len(), 0);
+    }
+
+    #[test]
+    fn ray_misses_triangle_edges() {
+        let t = triangle();
+        let origins = [
+            Tuple::point(1.0, 1.0, -2.0),
+            Tuple::point(-1.0, 1.0, -2.0),
+            Tuple::point(0.0, -1.0, -2.0),
+        ];
+
+        for origin in origins {
+            let r = Ray::new(origin, Tuple::vector(0.0, 0.0, 1.0));
+            assert_eq!(t.local_intersect(&r).len(), 0);
+        }
+    }
+
+    #[test]
+    fn ray_strikes_triangle() {
+        let t = triangle();
+        let r = Ray::new(Tuple::point(0.0, 0.5, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        let xs = t.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].time, 2.0);
+    }
+
+    #[test]
+    fn construct_smooth_triangle() {
+        let t = smooth_triangle();
+
+        assert_eq!(t.p1, Tuple::point(0.0, 1.0, 0.0));
+        assert_eq!(t.n2, Tuple::vector(-1.0, 0.0, 0.0));
+        assert_eq!(t.e1, Tuple::vector(-1.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn intersection_with_smooth_triangle_stores_uv() {
+        let t = smooth_triangle();
+        let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        let xs = t.local_intersect(&r);
+
+        assert!((xs[0].u - 0.45).abs() < utils::F64_ERROR_MARGIN);
+        assert!((xs[0].v - 0.25).abs() < utils::F64_ERROR_MARGIN);
+    }
+
+    #[test]
+    fn smooth_triangle_interpolates_normal() {
+        let t = smooth_triangle();
+        let i = Intersection::with_uv(1.0, &t, 0.45, 0.25);
+
+        let n = t.normal_at_hit(&Tuple::origin(), &i);
+
+        assert_eq!(
+            n,
+            Tuple::vector(-0.5547001962252291, 0.8320502943378436, 0.0)
+        );
+    }
+
+    #[test]
+    fn smooth_triangle_recovers_uv_from_point() {
+        let t = smooth_triangle();
+
+        let n = t.normal_at(&Tuple::point(-0.2, 0.3, 0.0));
+
+        assert_eq!(
+            n,
+            Tuple::vector(-0.5547001962252291, 0.8320502943378436, 0.0)
+        );
+    }
+
+    #[test]
+    fn prepare_normal_on_smooth_triangle() {
+        let t = smooth_triangle();
+        let i = Intersection::with_uv(1.0, &t, 0.45, 0.25);
+        let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        let comps = i.prepare_computations(&r);
+
+        assert_eq!(
+            comps.normal_vector,
+            Tuple::vector(-0.5547001962252291, 0.8320502943378436, 0.0)
+        );
+    }
+
+    #[test]
+    fn ray_strikes_tiny_triangle() {
+        let t = Triangle::new(
+            Tuple::point(0.0, 0.001, 0.0),
+            Tuple::point(-0.001, 0.0, 0.0),
+            Tuple::point(0.001, 0.0, 0.0),
+        );
+        let r = Ray::new(
+            Tuple::point(0.0, 0.0005, -2.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+        );
+
+        let xs = t.local_intersect(&r);
+
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].time, 2.0);
+    }
+}
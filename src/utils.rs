@@ -1 +1,9 @@
 pub(crate) const F64_ERROR_MARGIN: f64 = 1.0E-10;
+
+/// How far a point is nudged off a surface before casting secondary rays from it,
+/// so that the ray does not immediately re-intersect the surface it started on ("acne").
+pub(crate) const SURFACE_EPSILON: f64 = 1.0E-5;
+
+/// How small a quantity may be, as a fraction of the lengths it was computed from, before a
+/// ray counts as parallel to a surface. Being relative, it holds for tiny and huge shapes alike.
+pub(crate) const PARALLEL_EPSILON: f64 = 1.0E-12;
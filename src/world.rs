@@ -0,0 +1,682 @@
+use crate::color::Color;
+use crate::environment::Environment;
+use crate::group::Group;
+use crate::intersection::{Computations, Intersections};
+use crate::lights::{lighting, Light, PointLight};
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::sphere::Sphere;
+use crate::transformations::scaling;
+use crate::tuple::Tuple;
+
+/// A collection of objects and light sources that together make up a scene.
+pub struct World {
+    pub objects: Vec<Box<dyn Shape>>,
+    pub lights: Vec<Box<dyn Light>>,
+    /// What rays that miss every object see. Without one they come back black.
+    pub environment: Option<Environment>,
+    /// How many times a ray may bounce off reflective surfaces before we give up on it, so
+    /// that two facing mirrors do not recurse forever.
+    pub max_depth: usize,
+}
+
+/// Enough bounces for mirrors within mirrors, without tracing rays that barely contribute.
+pub const DEFAULT_MAX_DEPTH: usize = 5;
+
+impl World {
+    pub fn new() -> Self {
+        Self {
+            objects: vec![],
+            lights: vec![],
+            environment: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Two concentric spheres lit by a single white point light, used throughout the tests.
+    pub fn default() -> Self {
+        let light = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+
+        let mut s1 = Sphere::unit();
+        let mut m1 = Material::default();
+        m1.color = Color::new(0.8, 1.0, 0.6);
+        m1.diffuse = 0.7;
+        m1.specular = 0.2;
+        s1.set_material(&m1);
+
+        let mut s2 = Sphere::unit();
+        s2.set_transform(&scaling(0.5, 0.5, 0.5));
+
+        Self {
+            objects: vec![Box::new(s1), Box::new(s2)],
+            lights: vec![Box::new(light)],
+            environment: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    pub fn add_object(&mut self, object: Box<dyn Shape>) {
+        self.objects.push(object);
+    }
+
+    pub fn add_light(&mut self, light: Box<dyn Light>) {
+        self.lights.push(light);
+    }
+
+    /// Gathers all objects into one group and builds a bounding volume hierarchy over it, so
+    /// that rays skip whole clusters of objects at once. Call it after the scene is complete.
+    pub fn divide(&mut self, threshold: usize) {
+        let mut root = Group::new();
+        for object in self.objects.drain(..) {
+            root.add_child(object);
+        }
+        root.divide(threshold);
+        self.objects = vec![Box::new(root)];
+    }
+
+    pub fn intersect_world(&self, ray: &Ray) -> Intersections<'_> {
+        let mut intersections = Intersections { objects: vec![] };
+        for object in &self.objects {
+            intersections
+                .objects
+                .extend(ray.intersect(object.as_ref()).objects);
+        }
+        intersections.sort();
+
+        intersections
+    }
+
+    /// The color at the hit described by `comps`, with `remaining` bounces left for the
+    /// rays it spawns. Every light is shaded with the fraction of it the point can see.
+    pub fn shade_hit(&self, comps: &Computations, remaining: usize) -> Color {
+        let material = comps.object.get_material();
+
+        let point = &comps.over_point;
+        let lights: Vec<(&dyn Light, f64)> = self
+            .lights
+            .iter()
+            // Points the light does not shine towards need no shadow rays.
+            .filter(|light| light.falloff(point) > 0.0)
+            .map(|light| (light.as_ref(), light.intensity_at(point, self)))
+            .collect();
+        let surface = lighting(
+            material,
+            comps.object,
+            &lights,
+            point,
+            &comps.eye_vector,
+            &comps.normal_vector,
+        );
+
+        let reflected = self.reflected_color(comps, remaining);
+        let refracted = self.refracted_color(comps, remaining);
+
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            // Blend by the Fresnel effect: surfaces mirror more when viewed at a grazing angle.
+            let reflectance = comps.schlick();
+            surface + reflected * reflectance + refracted * (1.0 - reflectance)
+        } else {
+            surface + reflected + refracted
+        }
+    }
+
+    /// The color seen in the mirror image at the hit, if the surface is reflective at all.
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let reflective = comps.object.get_material().reflective;
+        if remaining == 0 || reflective == 0.0 {
+            return Color::black();
+        }
+
+        let reflect_ray = Ray::new(comps.over_point, comps.reflect_vector);
+        self.color_at_depth(&reflect_ray, remaining - 1) * reflective
+    }
+
+    /// The color seen through the surface at the hit, bent according to Snell's law.
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Color {
+        let transparency = comps.object.get_material().transparency;
+        if remaining == 0 || transparency == 0.0 {
+            return Color::black();
+        }
+
+        let n_ratio = comps.n1 / comps.n2;
+        let cos_i = comps.eye_vector.dot(&comps.normal_vector);
+        let sin2_t = n_ratio * n_ratio * (1.0 - cos_i * cos_i);
+        if sin2_t > 1.0 {
+            // Total internal reflection: no light makes it through.
+            return Color::black();
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let direction =
+            comps.normal_vector * (n_ratio * cos_i - cos_t) - comps.eye_vector * n_ratio;
+        let refract_ray = Ray::new(comps.under_point, direction);
+
+        self.color_at_depth(&refract_ray, remaining - 1) * transparency
+    }
+
+    /// Casts a ray from `point` towards `light_position` and checks whether anything lies in
+    /// between.
+    pub fn is_shadowed(&self, light_position: &Tuple, point: &Tuple) -> bool {
+        let point_to_light = *light_position - *point;
+        self.is_shadowed_along(point, &point_to_light.norm(), point_to_light.abs())
+    }
+
+    /// Casts a ray from `point` along the unit vector `direction` and checks whether anything
+    /// lies within `distance`, which may be infinite.
+    pub fn is_shadowed_along(&self, point: &Tuple, direction: &Tuple, distance: f64) -> bool {
+        let ray = Ray::new(*point, *direction);
+
+        match self.intersect_world(&ray).get_hit() {
+            None => false,
+            Some(hit) => hit.time < distance,
+        }
+    }
+
+    pub fn color_at(&self, ray: &Ray) -> Color {
+        self.color_at_depth(ray, self.max_depth)
+    }
+
+    pub fn color_at_depth(&self, ray: &Ray, remaining: usize) -> Color {
+        let xs = self.intersect_world(ray);
+        match xs.get_hit() {
+            None => self.background_color(ray),
+            Some(hit) => self.shade_hit(&hit.prepare_computations_with(ray, &xs), remaining),
+        }
+    }
+
+    /// The color seen by a ray that leaves the scene without hitting anything.
+    fn background_color(&self, ray: &Ray) -> Color {
+        match &self.environment {
+            Some(environment) => environment.color_at(&ray.direction),
+            None => Color::black(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intersection::Intersection;
+    use crate::plane::Plane;
+    use crate::texture::UvChecker;
+    use crate::transformations::translation;
+    use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+    use std::rc::Rc;
+
+    #[test]
+    fn create_empty_world() {
+        let w = World::new();
+
+        assert_eq!(w.objects.len(), 0);
+        assert_eq!(w.lights.len(), 0);
+    }
+
+    #[test]
+    fn default_world_has_two_spheres_and_a_light() {
+        let w = World::default();
+
+        assert_eq!(w.objects.len(), 2);
+        assert_eq!(w.lights.len(), 1);
+        assert_eq!(*w.objects[1].get_transform(), scaling(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn intersect_world_with_ray() {
+        let w = World::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        let xs = w.intersect_world(&r);
+
+        assert_eq!(xs.len(), 4);
+        assert_eq!(xs[0].time, 4.0);
+        assert_eq!(xs[1].time, 4.5);
+        assert_eq!(xs[2].time, 5.5);
+        assert_eq!(xs[3].time, 6.0);
+    }
+
+    #[test]
+    fn divided_world_intersects_the_same() {
+        let mut w = World::default();
+        w.divide(1);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        let xs = w.intersect_world(&r);
+
+        assert_eq!(w.objects.len(), 1);
+        assert_eq!(xs.len(), 4);
+        assert_eq!(xs[0].time, 4.0);
+        assert_eq!(xs[3].time, 6.0);
+    }
+
+    #[test]
+    fn shade_intersection() {
+        let w = World::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, w.objects[0].as_ref());
+
+        let comps = i.prepare_computations(&r);
+        let c = w.shade_hit(&comps, DEFAULT_MAX_DEPTH);
+
+        assert_eq!(
+            c,
+            Color::new(0.3806609553101071, 0.47582619413763383, 0.2854957164825803)
+        );
+    }
+
+    #[test]
+    fn shade_intersection_from_inside() {
+        let mut w = World::default();
+        w.lights = vec![Box::new(PointLight::new(
+            Tuple::point(0.0, 0.25, 0.0),
+            Color::new(1.0, 1.0, 1.0),
+        ))];
+        let r = Ray::new(Tuple::origin(), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(0.5, w.objects[1].as_ref());
+
+        let comps = i.prepare_computations(&r);
+        let c = w.shade_hit(&comps, DEFAULT_MAX_DEPTH);
+
+        assert_eq!(
+            c,
+            Color::new(0.9049812520679432, 0.9049812520679432, 0.9049812520679432)
+        );
+    }
+
+    #[test]
+    fn color_when_ray_misses() {
+        let w = World::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        assert_eq!(w.color_at(&r), Color::black());
+    }
+
+    #[test]
+    fn color_when_ray_misses_environment() {
+        let mut w = World::default();
+        let sky = Color::new(0.4, 0.6, 0.9);
+        w.environment = Some(Environment::new(Rc::new(UvChecker::new(1, 1, sky, sky))));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+
+        assert_eq!(w.color_at(&r), sky);
+    }
+
+    #[test]
+    fn color_when_ray_hits() {
+        let w = World::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+        assert_eq!(
+            w.color_at(&r),
+            Color::new(0.3806609553101071, 0.47582619413763383, 0.2854957164825803)
+        );
+    }
+
+    #[test]
+    fn color_with_intersection_behind_ray() {
+        let mut w = World::default();
+        let mut outer_material = w.objects[0].get_material().clone();
+        outer_material.ambient = 1.0;
+        w.objects[0].set_material(&outer_material);
+
+        let mut inner_material = w.objects[1].get_material().clone();
+        inner_material.ambient = 1.0;
+        w.objects[1].set_material(&inner_material);
+
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.75), Tuple::vector(0.0, 0.0, -1.0));
+
+        assert_eq!(w.color_at(&r), inner_material.color);
+    }
+
+    #[test]
+    fn color_sums_contributions_of_all_lights() {
+        let mut w = World::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let single = w.color_at(&r);
+        let material = w.objects[0].get_material();
+        let ambient = material.color * material.ambient;
+
+        w.add_light(Box::new(PointLight::new(
+            Tuple::point(-10.0, 10.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        )));
+
+        // The ambient term is counted once, however many lights there are.
+        assert_eq!(w.color_at(&r), single * 2.0 - ambient);
+    }
+
+    #[test]
+    fn no_shadow_when_nothing_is_collinear_with_point_and_light() {
+        let w = World::default();
+        let light_position = Tuple::point(-10.0, 10.0, -10.0);
+        let p = Tuple::point(0.0, 10.0, 0.0);
+
+        assert!(!w.is_shadowed(&light_position, &p));
+    }
+
+    #[test]
+    fn shadow_when_object_between_point_and_light() {
+        let w = World::default();
+        let light_position = Tuple::point(-10.0, 10.0, -10.0);
+        let p = Tuple::point(10.0, -10.0, 10.0);
+
+        assert!(w.is_shadowed(&light_position, &p));
+    }
+
+    #[test]
+    fn no_shadow_when_object_behind_light() {
+        let w = World::default();
+        let light_position = Tuple::point(-10.0, 10.0, -10.0);
+        let p = Tuple::point(-20.0, 20.0, -20.0);
+
+        assert!(!w.is_shadowed(&light_position, &p));
+    }
+
+    #[test]
+    fn no_shadow_when_object_behind_point() {
+        let w = World::default();
+        let light_position = Tuple::point(-10.0, 10.0, -10.0);
+        let p = Tuple::point(-2.0, 2.0, -2.0);
+
+        assert!(!w.is_shadowed(&light_position, &p));
+    }
+
+    #[test]
+    fn shade_hit_given_intersection_in_shadow() {
+        let mut w = World::new();
+        w.add_light(Box::new(PointLight::new(
+            Tuple::point(0.0, 0.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        )));
+        w.add_object(Box::new(Sphere::unit()));
+        let mut s2 = Sphere::unit();
+        s2.set_transform(&translation(0.0, 0.0, 10.0));
+        w.add_object(Box::new(s2));
+
+        let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, w.objects[1].as_ref());
+
+        let comps = i.prepare_computations(&r);
+
+        assert_eq!(
+            w.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.1, 0.1, 0.1)
+        );
+    }
+
+    #[test]
+    fn shade_hit_skips_only_the_shadowed_lights() {
+        let mut w = World::new();
+        w.add_light(Box::new(PointLight::new(
+            Tuple::point(0.0, 0.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        )));
+        w.add_light(Box::new(PointLight::new(
+            Tuple::point(0.0, 0.0, 5.0),
+            Color::new(1.0, 1.0, 1.0),
+        )));
+        w.add_object(Box::new(Sphere::unit()));
+        let mut s2 = Sphere::unit();
+        s2.set_transform(&translation(0.0, 0.0, 10.0));
+        w.add_object(Box::new(s2));
+
+        let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, w.objects[1].as_ref());
+
+        let comps = i.prepare_computations(&r);
+
+        // The first light is blocked by the unit sphere, the second shines head-on.
+        assert_eq!(
+            w.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(1.9, 1.9, 1.9)
+        );
+    }
+
+    /// The default world with a half-reflective plane just below the spheres.
+    fn world_with_mirror_floor() -> World {
+        let mut w = World::default();
+        let mut floor = Plane::new();
+        let mut material = Material::default();
+        material.reflective = 0.5;
+        floor.set_material(&material);
+        floor.set_transform(&translation(0.0, -1.0, 0.0));
+        w.add_object(Box::new(floor));
+        w
+    }
+
+    fn ray_towards_floor() -> Ray {
+        Ray::new(
+            Tuple::point(0.0, 0.0, -3.0),
+            Tuple::vector(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        )
+    }
+
+    #[test]
+    fn reflected_color_for_nonreflective_material() {
+        let mut w = World::default();
+        let r = Ray::new(Tuple::origin(), Tuple::vector(0.0, 0.0, 1.0));
+        let mut inner_material = w.objects[1].get_material().clone();
+        inner_material.ambient = 1.0;
+        w.objects[1].set_material(&inner_material);
+        let i = Intersection::new(1.0, w.objects[1].as_ref());
+
+        let comps = i.prepare_computations(&r);
+
+        assert_eq!(w.reflected_color(&comps, DEFAULT_MAX_DEPTH), Color::black());
+    }
+
+    #[test]
+    fn reflected_color_for_reflective_material() {
+        let w = world_with_mirror_floor();
+        let r = ray_towards_floor();
+        let i = Intersection::new(SQRT_2, w.objects[2].as_ref());
+
+        let comps = i.prepare_computations(&r);
+
+        assert_eq!(
+            w.reflected_color(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.190332201495133, 0.23791525186891627, 0.14274915112134975)
+        );
+    }
+
+    #[test]
+    fn reflected_color_of_environment() {
+        let mut w = World::new();
+        let sky = Color::new(0.4, 0.6, 0.9);
+        w.environment = Some(Environment::new(Rc::new(UvChecker::new(1, 1, sky, sky))));
+        let mut mirror = Plane::new();
+        let mut material = Material::default();
+        material.reflective = 0.5;
+        mirror.set_material(&material);
+        mirror.set_transform(&translation(0.0, -1.0, 0.0));
+        w.add_object(Box::new(mirror));
+        let r = ray_towards_floor();
+
+        let xs = r.intersect(w.objects[0].as_ref());
+        let comps = xs[0].prepare_computations(&r);
+
+        assert_eq!(w.reflected_color(&comps, DEFAULT_MAX_DEPTH), sky * 0.5);
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_material() {
+        let w = world_with_mirror_floor();
+        let r = ray_towards_floor();
+        let i = Intersection::new(SQRT_2, w.objects[2].as_ref());
+
+        let comps = i.prepare_computations(&r);
+
+        assert_eq!(
+            w.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.8767572837020907, 0.924340334075874, 0.8291742333283075)
+        );
+    }
+
+    #[test]
+    fn reflected_color_at_maximum_depth() {
+        let w = world_with_mirror_floor();
+        let r = ray_towards_floor();
+        let i = Intersection::new(SQRT_2, w.objects[2].as_ref());
+
+        let comps = i.prepare_computations(&r);
+
+        assert_eq!(w.reflected_color(&comps, 0), Color::black());
+    }
+
+    #[test]
+    fn color_at_with_mutually_reflective_surfaces_terminates() {
+        let mut w = World::new();
+        w.add_light(Box::new(PointLight::new(
+            Tuple::origin(),
+            Color::new(1.0, 1.0, 1.0),
+        )));
+        let mut material = Material::default();
+        material.reflective = 1.0;
+        let mut lower = Plane::new();
+        lower.set_material(&material);
+        lower.set_transform(&translation(0.0, -1.0, 0.0));
+        let mut upper = Plane::new();
+        upper.set_material(&material);
+        upper.set_transform(&translation(0.0, 1.0, 0.0));
+        w.add_object(Box::new(lower));
+        w.add_object(Box::new(upper));
+        let r = Ray::new(Tuple::origin(), Tuple::vector(0.0, 1.0, 0.0));
+
+        // The first hit and each of the five bounces adds 1.9, then the ray is dropped.
+        assert_eq!(w.color_at(&r), Color::new(11.4, 11.4, 11.4));
+    }
+
+    #[test]
+    fn refracted_color_with_opaque_surface() {
+        let w = World::default();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(4.0, w.objects[0].as_ref()),
+            Intersection::new(6.0, w.objects[0].as_ref()),
+        ]);
+
+        let comps = xs[0].prepare_computations_with(&r, &xs);
+
+        assert_eq!(w.refracted_color(&comps, DEFAULT_MAX_DEPTH), Color::black());
+    }
+
+    /// The default world with its outer sphere made of glass.
+    fn world_with_glass_shell() -> World {
+        let mut w = World::default();
+        let mut material = w.objects[0].get_material().clone();
+        material.transparency = 1.0;
+        material.refractive_index = 1.5;
+        w.objects[0].set_material(&material);
+        w
+    }
+
+    #[test]
+    fn refracted_color_at_maximum_depth() {
+        let w = world_with_glass_shell();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(4.0, w.objects[0].as_ref()),
+            Intersection::new(6.0, w.objects[0].as_ref()),
+        ]);
+
+        let comps = xs[0].prepare_computations_with(&r, &xs);
+
+        assert_eq!(w.refracted_color(&comps, 0), Color::black());
+    }
+
+    #[test]
+    fn refracted_color_under_total_internal_reflection() {
+        let w = world_with_glass_shell();
+        let r = Ray::new(
+            Tuple::point(0.0, 0.0, FRAC_1_SQRT_2),
+            Tuple::vector(0.0, 1.0, 0.0),
+        );
+        let xs = Intersections::new(vec![
+            Intersection::new(-FRAC_1_SQRT_2, w.objects[0].as_ref()),
+            Intersection::new(FRAC_1_SQRT_2, w.objects[0].as_ref()),
+        ]);
+
+        // We are inside the sphere, so look at the second intersection.
+        let comps = xs[1].prepare_computations_with(&r, &xs);
+
+        assert_eq!(w.refracted_color(&comps, DEFAULT_MAX_DEPTH), Color::black());
+    }
+
+    #[test]
+    fn refracted_color_with_refracted_ray() {
+        let mut w = World::default();
+        let mut outer_material = w.objects[0].get_material().clone();
+        outer_material.ambient = 1.0;
+        w.objects[0].set_material(&outer_material);
+        let mut inner_material = w.objects[1].get_material().clone();
+        inner_material.transparency = 1.0;
+        inner_material.refractive_index = 1.5;
+        w.objects[1].set_material(&inner_material);
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.1), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(-0.9899, w.objects[0].as_ref()),
+            Intersection::new(-0.4899, w.objects[1].as_ref()),
+            Intersection::new(0.4899, w.objects[1].as_ref()),
+            Intersection::new(0.9899, w.objects[0].as_ref()),
+        ]);
+
+        let comps = xs[2].prepare_computations_with(&r, &xs);
+
+        // The refracted ray hits the inside of the outer sphere, where it is lit only by
+        // its ambient light.
+        assert_eq!(
+            w.refracted_color(&comps, DEFAULT_MAX_DEPTH),
+            outer_material.color
+        );
+    }
+
+    /// The default world with a glass floor, and a red ball below the floor.
+    fn world_with_glass_floor(reflective: f64) -> World {
+        let mut w = World::default();
+        let mut floor = Plane::new();
+        floor.set_transform(&translation(0.0, -1.0, 0.0));
+        let mut floor_material = Material::default();
+        floor_material.reflective = reflective;
+        floor_material.transparency = 0.5;
+        floor_material.refractive_index = 1.5;
+        floor.set_material(&floor_material);
+        w.add_object(Box::new(floor));
+
+        let mut ball = Sphere::unit();
+        ball.set_transform(&translation(0.0, -3.5, -0.5));
+        let mut ball_material = Material::default();
+        ball_material.color = Color::new(1.0, 0.0, 0.0);
+        ball_material.ambient = 0.5;
+        ball.set_material(&ball_material);
+        w.add_object(Box::new(ball));
+        w
+    }
+
+    #[test]
+    fn shade_hit_with_transparent_material() {
+        let w = world_with_glass_floor(0.0);
+        let r = ray_towards_floor();
+        let xs = Intersections::new(vec![Intersection::new(SQRT_2, w.objects[2].as_ref())]);
+
+        let comps = xs[0].prepare_computations_with(&r, &xs);
+
+        assert_eq!(
+            w.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.9364250822069577, 0.6864250822069577, 0.6864250822069577)
+        );
+    }
+
+    #[test]
+    fn shade_hit_with_reflective_transparent_material() {
+        let w = world_with_glass_floor(0.5);
+        let r = ray_towards_floor();
+        let xs = Intersections::new(vec![Intersection::new(SQRT_2, w.objects[2].as_ref())]);
+
+        let comps = xs[0].prepare_computations_with(&r, &xs);
+
+        assert_eq!(
+            w.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Color::new(0.9339149012949264, 0.696434003918285, 0.6924304352337541)
+        );
+    }
+}